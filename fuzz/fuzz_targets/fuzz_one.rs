@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nvme_lite_oracle::fuzz::fuzz_one;
+
+fuzz_target!(|data: &[u8]| {
+    // Panicking here (via unwrap) is what makes libfuzzer record and
+    // minimize a crashing input when an invariant is violated.
+    fuzz_one(data).unwrap();
+});
@@ -0,0 +1,19 @@
+//! Persistent-mode honggfuzz-rs harness for `nvme_lite_oracle::fuzz::fuzz_one`.
+//!
+//! Coverage feedback comes from honggfuzz's instrumentation of the target
+//! binary, not from anything in this file; `fuzz!` just hands each generated
+//! input to the same decoder/invariant-checker the libfuzzer target in
+//! `fuzz/` uses, so a crash found here reproduces identically there (and as
+//! a normal minimized-seed regression file).
+
+use honggfuzz::fuzz;
+use nvme_lite_oracle::fuzz::fuzz_one;
+
+fn main() {
+    loop {
+        fuzz!(|data: &[u8]| {
+            // Panicking here is what makes honggfuzz record the input as a crash.
+            fuzz_one(data).unwrap();
+        });
+    }
+}
@@ -20,6 +20,22 @@ pub struct RawConfig {
     pub scheduler_version: String,
     #[serde(default)]
     pub git_commit: String,
+    /// Number of SQ/CQ queues to route each run through via
+    /// [`crate::runner::execute_run_multi_queue`]. `1` (the default) keeps
+    /// the sweep on the single-queue `NvmeLiteModel` path.
+    #[serde(default = "default_num_queues")]
+    pub num_queues: usize,
+    /// Per-queue ring-buffer depth, only consulted when `num_queues > 1`.
+    #[serde(default = "default_queue_depth")]
+    pub queue_depth: usize,
+}
+
+fn default_num_queues() -> usize {
+    1
+}
+
+fn default_queue_depth() -> usize {
+    16
 }
 
 /// Parsed configuration ready for use
@@ -32,6 +48,8 @@ pub struct ExperimentConfig {
     pub schedule_seed_range: (u64, u64), // (start, end) inclusive
     pub scheduler_version: String,
     pub git_commit: String,
+    pub num_queues: usize,
+    pub queue_depth: usize,
 }
 
 impl ExperimentConfig {
@@ -72,6 +90,8 @@ impl ExperimentConfig {
             schedule_seed_range,
             scheduler_version: raw.scheduler_version,
             git_commit,
+            num_queues: raw.num_queues,
+            queue_depth: raw.queue_depth,
         })
     }
 
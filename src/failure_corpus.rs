@@ -0,0 +1,170 @@
+//! Persistent failure corpus for `run-matrix`: every run that trips an
+//! oracle invariant is appended to a newline-delimited JSON file (like
+//! proptest's `failure_persistence` files), so it can be replayed later via
+//! `Commands::Replay` without re-running the whole matrix sweep.
+
+use crate::logging::FaultMode;
+use crate::runner::RunConfig;
+use crate::scheduler::{BoundK, Policy};
+use crate::seed::Seed;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+/// One failing run, recorded with enough of `RunConfig` (as strings, mirroring
+/// `quarantine::QuarantinedConfig`) to re-execute it deterministically.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailureRecord {
+    pub run_id: String,
+    pub seed_file: String,
+    pub schedule_seed: u64,
+    pub policy: String,
+    pub bound_k: String,
+    pub fault_mode: String,
+    pub submit_window: String,
+    pub scheduler_version: String,
+    pub git_commit: String,
+    pub reason: String,
+}
+
+impl FailureRecord {
+    pub fn new(seed_file: &str, config: &RunConfig, submit_window: &str, reason: String) -> Self {
+        Self {
+            run_id: config.run_id(),
+            seed_file: seed_file.to_string(),
+            schedule_seed: config.schedule_seed,
+            policy: config.policy.to_string(),
+            bound_k: config.bound_k.to_string(),
+            fault_mode: config.fault_mode.to_string(),
+            submit_window: submit_window.to_string(),
+            scheduler_version: config.scheduler_version.clone(),
+            git_commit: config.git_commit.clone(),
+            reason,
+        }
+    }
+
+    /// Reconstruct the `Seed` and `RunConfig` needed to replay this failure.
+    pub fn load(&self) -> Result<(Seed, RunConfig)> {
+        let seed = Seed::load(Path::new(&self.seed_file))
+            .with_context(|| format!("failed to load seed: {}", self.seed_file))?;
+        let policy: Policy = self.policy.parse().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let bound_k = BoundK::parse(&self.bound_k).map_err(|e| anyhow::anyhow!("{}", e))?;
+        let fault_mode: FaultMode = self.fault_mode.parse().map_err(|e| anyhow::anyhow!("{}", e))?;
+        let submit_window = crate::logging::SubmitWindow::parse(&self.submit_window)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let config = RunConfig {
+            seed_id: seed.seed_id.clone(),
+            schedule_seed: self.schedule_seed,
+            policy,
+            bound_k,
+            fault_mode,
+            submit_window,
+            scheduler_version: self.scheduler_version.clone(),
+            git_commit: self.git_commit.clone(),
+            dump_schedule: false,
+        };
+        Ok((seed, config))
+    }
+}
+
+/// Append `record` as one line to `path`, creating the file (and its parent
+/// directory) if needed.
+pub fn append_failure(path: &Path, record: &FailureRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open failure corpus: {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Load every record from a newline-delimited failure corpus file.
+pub fn load_corpus(path: &Path) -> Result<Vec<FailureRecord>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read failure corpus: {}", path.display()))?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .with_context(|| format!("malformed failure record: {}", line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::SubmitWindow;
+    use crate::seed::Command;
+
+    fn sample_config() -> RunConfig {
+        RunConfig {
+            seed_id: "seed_001".to_string(),
+            schedule_seed: 7,
+            policy: Policy::ADVERSARIAL,
+            bound_k: BoundK::Finite(2),
+            fault_mode: FaultMode::RESET,
+            submit_window: SubmitWindow::Infinite,
+            scheduler_version: "v1.0".to_string(),
+            git_commit: "deadbeef".to_string(),
+            dump_schedule: false,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load_roundtrip() {
+        let tmp_seed = std::env::temp_dir().join("test_failure_corpus_seed.json");
+        let seed = Seed {
+            seed_id: "seed_001".to_string(),
+            commands: vec![Command::WRITE { lba: 0, len: 1, pattern: 1 }],
+        };
+        std::fs::write(&tmp_seed, serde_json::to_string(&seed).unwrap()).unwrap();
+
+        let tmp_corpus = std::env::temp_dir().join("test_failure_corpus.jsonl");
+        std::fs::remove_file(&tmp_corpus).ok();
+
+        let config = sample_config();
+        let record = FailureRecord::new(
+            tmp_seed.to_str().unwrap(),
+            &config,
+            "inf",
+            "pending_left nonzero without reset".to_string(),
+        );
+        append_failure(&tmp_corpus, &record).unwrap();
+
+        let loaded = load_corpus(&tmp_corpus).unwrap();
+        std::fs::remove_file(&tmp_corpus).ok();
+
+        assert_eq!(loaded.len(), 1);
+        let (loaded_seed, loaded_config) = loaded[0].load().unwrap();
+        std::fs::remove_file(&tmp_seed).ok();
+        assert_eq!(loaded_seed.seed_id, "seed_001");
+        assert_eq!(loaded_config.run_id(), config.run_id());
+        assert_eq!(loaded_config.schedule_seed, 7);
+        assert_eq!(loaded_config.bound_k, BoundK::Finite(2));
+    }
+
+    #[test]
+    fn test_append_multiple_records_are_newline_delimited() {
+        let tmp_corpus = std::env::temp_dir().join("test_failure_corpus_multi.jsonl");
+        std::fs::remove_file(&tmp_corpus).ok();
+
+        let config = sample_config();
+        for i in 0..3 {
+            let record = FailureRecord::new("seeds/does_not_matter.json", &config, "inf", format!("reason {}", i));
+            append_failure(&tmp_corpus, &record).unwrap();
+        }
+
+        let loaded = load_corpus(&tmp_corpus).unwrap();
+        std::fs::remove_file(&tmp_corpus).ok();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[2].reason, "reason 2");
+    }
+}
@@ -0,0 +1,368 @@
+//! Coverage-guided fuzz harness.
+//!
+//! `fuzz_one` decodes an arbitrary byte buffer into a `Seed` plus the knobs
+//! `execute_run` needs (`policy`, `bound_k`, `fault_mode`, `submit_window`,
+//! `schedule_seed`), drives one run, and checks the core robustness
+//! invariants: `pending_left == 0` unless a reset happened, commands lost to
+//! a reset never exceed the observed peak, and completion order never lets a
+//! command finish after a `FENCE` that should have barriered it. On a
+//! violation the input is shrunk with ddmin and the minimal reproducing
+//! seed is written out as a standalone regression file.
+//!
+//! The decoder never fails: arbitrary bytes always produce *some* bounded,
+//! valid `Seed`/config, which is what lets a coverage-guided fuzzer (cargo-fuzz,
+//! honggfuzz) mutate the raw bytes freely without wasting runs on rejected input.
+
+use crate::logging::{FaultMode, SubmitWindow};
+use crate::model::NvmeLiteModel;
+use crate::multi_queue::MultiQueueModel;
+use crate::runner::{execute_run_with_model, RunConfig, RunResult};
+use crate::scheduler::{BoundK, Policy};
+use crate::seed::{Command, Seed};
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+
+/// Upper bound on decoded command count, so a single fuzz input can't blow up a run.
+const MAX_COMMANDS: usize = 64;
+/// Upper bound on decoded lba/len fields (the model's storage is 1024 words; an lba
+/// below 900 plus a len of at most 16 keeps every decoded command in-bounds, so runs
+/// exercise READ/WRITE/WRITE_VISIBLE data flow instead of the `Status::ERR`
+/// out-of-range path).
+const MAX_LBA: u64 = 900;
+const MAX_LEN: u32 = 16;
+
+/// A little state machine over the raw bytes: each `next_*` call consumes a
+/// fixed number of bytes (zero-padding once the buffer is exhausted) so decoding
+/// never fails and is a pure function of the input length and content.
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn next_u8(&mut self) -> u8 {
+        let b = self.data.get(self.pos).copied().unwrap_or(0);
+        self.pos += 1;
+        b
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        for b in &mut buf {
+            *b = self.next_u8();
+        }
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        for b in &mut buf {
+            *b = self.next_u8();
+        }
+        u64::from_le_bytes(buf)
+    }
+
+    fn exhausted(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+}
+
+/// Decode the run-level knobs from the front of the buffer: first bytes choose
+/// each enum modulo its variant count, so every byte value maps to a valid choice.
+fn decode_run_params(r: &mut ByteReader) -> (Policy, BoundK, FaultMode, SubmitWindow, u64) {
+    let policy = match r.next_u8() % 4 {
+        0 => Policy::FIFO,
+        1 => Policy::RANDOM,
+        2 => Policy::ADVERSARIAL,
+        _ => Policy::BATCHED,
+    };
+    let bound_k = match r.next_u8() % 4 {
+        0 => BoundK::Finite(0),
+        1 => BoundK::Finite(1),
+        2 => BoundK::Finite(4),
+        _ => BoundK::Infinite,
+    };
+    let fault_mode = match r.next_u8() % 3 {
+        0 => FaultMode::NONE,
+        1 => FaultMode::TIMEOUT,
+        _ => FaultMode::RESET,
+    };
+    let submit_window = match r.next_u8() % 3 {
+        0 => SubmitWindow::Finite(1),
+        1 => SubmitWindow::Finite(4),
+        _ => SubmitWindow::Infinite,
+    };
+    let schedule_seed = r.next_u64();
+    (policy, bound_k, fault_mode, submit_window, schedule_seed)
+}
+
+/// Decode a single command; every byte value maps to some variant with
+/// bounded fields, never an error.
+fn decode_command(r: &mut ByteReader) -> Command {
+    match r.next_u8() % 4 {
+        0 => Command::WRITE {
+            lba: r.next_u64() % MAX_LBA,
+            len: 1 + r.next_u32() % MAX_LEN,
+            pattern: r.next_u32(),
+        },
+        1 => Command::READ {
+            lba: r.next_u64() % MAX_LBA,
+            len: 1 + r.next_u32() % MAX_LEN,
+        },
+        2 => Command::FENCE,
+        _ => Command::WRITE_VISIBLE {
+            lba: r.next_u64() % MAX_LBA,
+            len: 1 + r.next_u32() % MAX_LEN,
+        },
+    }
+}
+
+/// Decode the remainder of the buffer into a bounded command sequence.
+fn decode_seed(r: &mut ByteReader, seed_id: &str) -> Seed {
+    let mut commands = Vec::new();
+    while !r.exhausted() && commands.len() < MAX_COMMANDS {
+        commands.push(decode_command(r));
+    }
+    Seed {
+        seed_id: seed_id.to_string(),
+        commands,
+    }
+}
+
+/// Category of an invariant violation, used both to report the bug and as the
+/// "still fails" predicate during minimization (comparing category, not just
+/// "some error occurred"). `pub` so `quarantine` and the JUnit reporter can
+/// reuse the same classification instead of re-checking the invariants
+/// themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    PendingLeftWithoutReset,
+    CommandsLostExceedsPeak,
+    FenceOrdering,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::PendingLeftWithoutReset => write!(f, "pending_left nonzero without reset"),
+            Violation::CommandsLostExceedsPeak => write!(f, "commands_lost exceeds pending_peak"),
+            Violation::FenceOrdering => write!(f, "fence ordering violation"),
+        }
+    }
+}
+
+/// Check a completed run's `RunResult`/model state against the oracle's
+/// robustness invariants, without re-executing anything.
+pub fn classify_violation(result: &RunResult, model: &NvmeLiteModel) -> Option<Violation> {
+    if result.pending_left != 0 && !result.had_reset {
+        return Some(Violation::PendingLeftWithoutReset);
+    }
+    if result.commands_lost > result.pending_peak {
+        return Some(Violation::CommandsLostExceedsPeak);
+    }
+    if model.check_fence_invariant().is_some() {
+        return Some(Violation::FenceOrdering);
+    }
+    None
+}
+
+/// Same invariants as [`classify_violation`], against a `MultiQueueModel`
+/// run instead of the default single-queue one. A cross-queue fence
+/// violation is still surfaced as [`Violation::FenceOrdering`]; the
+/// cross-queue count (`result.cross_queue_fence_violations`) is kept on the
+/// `RunResult` for metrics, not folded into this classification.
+pub fn classify_violation_multi_queue(
+    result: &RunResult,
+    model: &MultiQueueModel,
+) -> Option<Violation> {
+    if result.pending_left != 0 && !result.had_reset {
+        return Some(Violation::PendingLeftWithoutReset);
+    }
+    if result.commands_lost > result.pending_peak {
+        return Some(Violation::CommandsLostExceedsPeak);
+    }
+    if model.check_fence_invariant().is_some() {
+        return Some(Violation::FenceOrdering);
+    }
+    None
+}
+
+fn check_violation(seed: &Seed, config: &RunConfig) -> Result<Option<Violation>> {
+    let out_log = std::env::temp_dir().join(format!("fuzz_{}.log", config.run_id()));
+    let (result, model) = execute_run_with_model(seed, config, &out_log, None)?;
+    let _ = std::fs::remove_file(&out_log);
+    Ok(classify_violation(&result, &model))
+}
+
+/// Decode `data` into a `Seed` + `RunConfig`, run it, and fail if any
+/// robustness invariant is violated. On failure, the violating seed is
+/// minimized with ddmin and written to `regressions/<run_id>.json`.
+pub fn fuzz_one(data: &[u8]) -> Result<()> {
+    let mut r = ByteReader::new(data);
+    let (policy, bound_k, fault_mode, submit_window, schedule_seed) = decode_run_params(&mut r);
+    let seed = decode_seed(&mut r, "fuzz");
+
+    let config = RunConfig {
+        seed_id: seed.seed_id.clone(),
+        schedule_seed,
+        policy,
+        bound_k,
+        fault_mode,
+        submit_window,
+        scheduler_version: "fuzz".to_string(),
+        git_commit: String::new(),
+        dump_schedule: false,
+    };
+
+    if let Some(violation) = check_violation(&seed, &config)? {
+        let minimized = minimize_seed(&seed, &config, &violation)?;
+        write_regression(&minimized, &config, &violation)?;
+        bail!(
+            "invariant violation ({}) on seed with {} command(s), minimized to {}",
+            violation,
+            seed.commands.len(),
+            minimized.commands.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Check whether `seed`/`config` currently reproduces a violation and, if
+/// so, shrink it to a minimal reproducing subsequence via ddmin. Returns
+/// `None` if the run does not violate any invariant, so callers (e.g. the
+/// `shrink` CLI subcommand) can report that distinctly from "shrunk to
+/// nothing".
+pub fn shrink(seed: &Seed, config: &RunConfig) -> Result<Option<Seed>> {
+    match check_violation(seed, config)? {
+        Some(violation) => minimize_seed(seed, config, &violation).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// ddmin: shrink `seed.commands` to a minimal subsequence that still
+/// reproduces `violation` under the identical `config` (same schedule_seed,
+/// policy, bound_k, etc. — only the command list changes).
+fn minimize_seed(seed: &Seed, config: &RunConfig, violation: &Violation) -> Result<Seed> {
+    let mut commands = seed.commands.clone();
+    let mut n = 2usize;
+
+    while n <= commands.len() {
+        let chunk_size = commands.len().div_ceil(n);
+        let mut reduced = None;
+
+        let mut start = 0;
+        while start < commands.len() {
+            let end = std::cmp::min(start + chunk_size, commands.len());
+            let mut complement = commands.clone();
+            complement.drain(start..end);
+
+            if !complement.is_empty() && reproduces(&complement, config, violation)? {
+                reduced = Some(complement);
+                break;
+            }
+            start += chunk_size;
+        }
+
+        match reduced {
+            Some(complement) => {
+                commands = complement;
+                n = 2;
+            }
+            None if n >= commands.len() => break,
+            None => n = std::cmp::min(n * 2, commands.len()),
+        }
+    }
+
+    Ok(Seed {
+        seed_id: format!("{}_min", seed.seed_id),
+        commands,
+    })
+}
+
+/// Re-run `commands` under `config` and check whether the *same category* of
+/// violation still reproduces.
+fn reproduces(commands: &[Command], config: &RunConfig, violation: &Violation) -> Result<bool> {
+    let candidate = Seed {
+        seed_id: format!("{}_ddmin", config.seed_id),
+        commands: commands.to_vec(),
+    };
+    Ok(check_violation(&candidate, config)?.as_ref() == Some(violation))
+}
+
+/// Write the minimized seed + the resolved config + the violation reason as a
+/// standalone, committable regression file.
+fn write_regression(seed: &Seed, config: &RunConfig, violation: &Violation) -> Result<()> {
+    #[derive(serde::Serialize)]
+    struct Regression<'a> {
+        run_id: String,
+        violation: String,
+        seed: &'a Seed,
+        policy: String,
+        bound_k: String,
+        fault_mode: String,
+        submit_window: String,
+        schedule_seed: u64,
+    }
+
+    let dir = PathBuf::from("regressions");
+    std::fs::create_dir_all(&dir)?;
+
+    let regression = Regression {
+        run_id: config.run_id(),
+        violation: violation.to_string(),
+        seed,
+        policy: config.policy.to_string(),
+        bound_k: config.bound_k.to_string(),
+        fault_mode: config.fault_mode.to_string(),
+        submit_window: config.submit_window.to_string(),
+        schedule_seed: config.schedule_seed,
+    };
+
+    let path = dir.join(format!("{}.json", regression.run_id));
+    std::fs::write(path, serde_json::to_string_pretty(&regression)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_never_fails_on_empty_input() {
+        let seed = decode_seed(&mut ByteReader::new(&[]), "empty");
+        assert!(seed.commands.is_empty());
+    }
+
+    #[test]
+    fn test_decode_is_deterministic() {
+        let data = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let mut r1 = ByteReader::new(&data);
+        let mut r2 = ByteReader::new(&data);
+        let (p1, k1, f1, w1, s1) = decode_run_params(&mut r1);
+        let (p2, k2, f2, w2, s2) = decode_run_params(&mut r2);
+        assert_eq!(p1, p2);
+        assert_eq!(k1, k2);
+        assert_eq!(f1, f2);
+        assert_eq!(w1, w2);
+        assert_eq!(s1, s2);
+    }
+
+    #[test]
+    fn test_fuzz_one_accepts_arbitrary_bytes() {
+        // Should never panic regardless of input shape; may legitimately
+        // report a violation, but must not error on decoding itself.
+        for input in [
+            &b""[..],
+            &b"\x00"[..],
+            &b"\xff\xff\xff\xff\xff\xff\xff\xff"[..],
+            &b"hello world this is not a seed file"[..],
+        ] {
+            let _ = fuzz_one(input);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Log compaction for `run-matrix` output, borrowing git gc's cruft-pack
+//! idea: a sweep's output directory fills up with thousands of mostly-boring
+//! `.log` files, so `gc` classifies each as "interesting" (anything worth a
+//! human looking at) or "cruft" (a clean run) and bundles the cruft logs
+//! into a single pack file, leaving interesting logs on disk untouched.
+//!
+//! Deviation from the original request: the request asked for cruft logs to
+//! be compacted into "a single compressed archive." This tree vendors no
+//! compression dependency (no manifest exists to add one to), and hand-rolling
+//! a compressor for this was judged not worth the risk over using the
+//! standard library alone, so the pack below is plain newline-delimited JSON
+//! with no byte-level compression — the win is fewer files to manage, not
+//! fewer bytes on disk; the log text itself is stored untouched. Flagging
+//! this explicitly rather than silently shipping an uncompressed "archive":
+//! revisit if/once a compression crate is added to the workspace.
+//!
+//! Classification reads the log's own `RESET(...)` / `RUN_END(...)` lines
+//! rather than re-executing the run, so it only sees what [`crate::logging`]
+//! already recorded: a reset (a proxy for `commands_lost`, which is only
+//! ever nonzero after a reset), a nonzero `pending_left` without a reset, or
+//! `pending_peak` at or above `peak_threshold`. Fence-ordering violations
+//! require re-running against the model and aren't visible from the log
+//! text alone, so they aren't detected here; `quarantine_if_violating` is
+//! the tool for that at run time.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// One cruft log, keyed by `run_id`, bundled into the pack file instead of
+/// left as a loose file — the same "keep what might matter, repack the
+/// rest" trade as git's cruft packs.
+#[derive(Debug, Serialize, Deserialize)]
+struct CruftEntry {
+    run_id: String,
+    log: String,
+}
+
+/// The pack file cruft logs are appended to, relative to the scanned `out_dir`.
+pub const PACK_FILE_NAME: &str = "cruft.pack.jsonl";
+
+/// Per-log verdict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Interesting,
+    Cruft,
+}
+
+/// Summary of one `gc` pass, printed by the CLI and used by tests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GcReport {
+    pub interesting: usize,
+    pub cruft: usize,
+    pub packed_bytes: u64,
+    pub dry_run: bool,
+}
+
+/// Parse a duration like `"30s"`, `"45m"`, `"24h"`, `"7d"`.
+pub fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let (num, unit) = s.split_at(s.len().saturating_sub(1));
+    let n: u64 = num
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration: {}", s))?;
+    let secs = match unit {
+        "s" => n,
+        "m" => n * 60,
+        "h" => n * 3600,
+        "d" => n * 86400,
+        _ => return Err(anyhow::anyhow!("invalid duration unit in: {} (use s/m/h/d)", s)),
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+/// Classify a log's content as interesting or cruft; see module docs for
+/// exactly what "interesting" covers.
+fn classify(content: &str, peak_threshold: u32) -> Verdict {
+    let had_reset = content.lines().any(|l| l.starts_with("RESET("));
+    if had_reset {
+        return Verdict::Interesting;
+    }
+
+    if let Some(line) = content.lines().find(|l| l.starts_with("RUN_END(")) {
+        let pending_left = parse_field(line, "pending_left").unwrap_or(0);
+        let pending_peak = parse_field(line, "pending_peak").unwrap_or(0);
+        if pending_left != 0 {
+            return Verdict::Interesting;
+        }
+        if peak_threshold > 0 && pending_peak >= peak_threshold {
+            return Verdict::Interesting;
+        }
+    }
+
+    Verdict::Cruft
+}
+
+/// Pull `key=value` out of a `NAME(a=1, b=2)`-style log line.
+fn parse_field(line: &str, key: &str) -> Option<u32> {
+    let needle = format!("{}=", key);
+    let start = line.find(&needle)? + needle.len();
+    let rest = &line[start..];
+    let end = rest.find([',', ')']).unwrap_or(rest.len());
+    rest[..end].trim().parse().ok()
+}
+
+/// Run one `gc` pass over `out_dir`.
+///
+/// `peak_threshold` of `0` disables the pending_peak check (only resets and
+/// pending_left leaks count as interesting). `older_than` restricts pruning
+/// to logs whose mtime is at least that old; `None` prunes regardless of
+/// age. `auto_threshold`, if set, makes this a no-op (reporting so) unless
+/// `out_dir` currently holds more than that many `.log` files — the
+/// `gc.auto`-style trigger for periodic compaction. `dry_run` reports what
+/// would happen without touching any file.
+pub fn run_gc(
+    out_dir: &Path,
+    peak_threshold: u32,
+    older_than: Option<Duration>,
+    auto_threshold: Option<usize>,
+    dry_run: bool,
+) -> Result<GcReport> {
+    let mut log_paths: Vec<PathBuf> = std::fs::read_dir(out_dir)
+        .with_context(|| format!("failed to read out_dir: {}", out_dir.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("log"))
+        .collect();
+    log_paths.sort();
+
+    if let Some(threshold) = auto_threshold {
+        if log_paths.len() <= threshold {
+            return Ok(GcReport {
+                dry_run,
+                ..Default::default()
+            });
+        }
+    }
+
+    let cutoff = older_than.map(|d| SystemTime::now() - d);
+
+    let mut report = GcReport {
+        dry_run,
+        ..Default::default()
+    };
+    let pack_path = out_dir.join(PACK_FILE_NAME);
+
+    for log_path in &log_paths {
+        if let Some(cutoff) = cutoff {
+            let modified = std::fs::metadata(log_path)?.modified()?;
+            if modified > cutoff {
+                continue;
+            }
+        }
+
+        let content = std::fs::read_to_string(log_path)
+            .with_context(|| format!("failed to read log: {}", log_path.display()))?;
+
+        match classify(&content, peak_threshold) {
+            Verdict::Interesting => report.interesting += 1,
+            Verdict::Cruft => {
+                report.cruft += 1;
+                report.packed_bytes += content.len() as u64;
+
+                if !dry_run {
+                    let run_id = log_path
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    append_to_pack(&pack_path, &CruftEntry { run_id, log: content })?;
+                    std::fs::remove_file(log_path)?;
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Append one cruft log to the pack as a JSON line. No compression is
+/// applied; consolidating logs into one file is the entire space/inode win.
+fn append_to_pack(pack_path: &Path, entry: &CruftEntry) -> Result<()> {
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(pack_path)
+        .with_context(|| format!("failed to open cruft pack: {}", pack_path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_log(dir: &Path, run_id: &str, body: &str) -> PathBuf {
+        let path = dir.join(format!("{}.log", run_id));
+        std::fs::write(&path, body).unwrap();
+        path
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        for entry in std::fs::read_dir(&dir).unwrap().flatten() {
+            std::fs::remove_file(entry.path()).ok();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_clean_run_is_cruft_and_gets_packed() {
+        let dir = temp_dir("test_gc_clean");
+        write_log(&dir, "run_clean", "RUN_HEADER(run_id=run_clean)\nRUN_END(pending_left=0, pending_peak=2)\n");
+
+        let report = run_gc(&dir, 0, None, None, false).unwrap();
+        assert_eq!(report.cruft, 1);
+        assert_eq!(report.interesting, 0);
+        assert!(!dir.join("run_clean.log").exists());
+
+        let pack = std::fs::read_to_string(dir.join(PACK_FILE_NAME)).unwrap();
+        assert!(pack.contains("run_clean"));
+    }
+
+    #[test]
+    fn test_reset_is_interesting_and_kept() {
+        let dir = temp_dir("test_gc_reset");
+        let log_path = write_log(
+            &dir,
+            "run_reset",
+            "RESET(reason=fault, pending_before=3)\nRUN_END(pending_left=0, pending_peak=3)\n",
+        );
+
+        let report = run_gc(&dir, 0, None, None, false).unwrap();
+        assert_eq!(report.interesting, 1);
+        assert_eq!(report.cruft, 0);
+        assert!(log_path.exists());
+    }
+
+    #[test]
+    fn test_peak_threshold_marks_interesting() {
+        let dir = temp_dir("test_gc_peak");
+        write_log(&dir, "run_peak", "RUN_END(pending_left=0, pending_peak=10)\n");
+
+        let report = run_gc(&dir, 8, None, None, false).unwrap();
+        assert_eq!(report.interesting, 1);
+        assert_eq!(report.cruft, 0);
+    }
+
+    #[test]
+    fn test_dry_run_leaves_files_untouched() {
+        let dir = temp_dir("test_gc_dry_run");
+        let log_path = write_log(&dir, "run_dry", "RUN_END(pending_left=0, pending_peak=1)\n");
+
+        let report = run_gc(&dir, 0, None, None, true).unwrap();
+        assert_eq!(report.cruft, 1);
+        assert!(report.dry_run);
+        assert!(log_path.exists());
+        assert!(!dir.join(PACK_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_auto_threshold_skips_below_file_count() {
+        let dir = temp_dir("test_gc_auto");
+        write_log(&dir, "run_a", "RUN_END(pending_left=0, pending_peak=1)\n");
+        write_log(&dir, "run_b", "RUN_END(pending_left=0, pending_peak=1)\n");
+
+        let report = run_gc(&dir, 0, None, Some(5), false).unwrap();
+        assert_eq!(report.cruft, 0);
+        assert_eq!(report.interesting, 0);
+        assert!(dir.join("run_a.log").exists());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(7 * 86400));
+        assert!(parse_duration("nope").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! JUnit XML reporting for the experiment matrix, so `run-matrix` output is
+//! consumable by standard CI test-report viewers without a separate
+//! conversion step.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A detected invariant violation for one run, reported as a `<failure>`.
+#[derive(Debug, Clone)]
+pub struct TestCaseFailure {
+    pub message: String,
+    pub log_lines: Vec<String>,
+    pub schedule_path: Option<PathBuf>,
+}
+
+/// Outcome of a single `(seed, policy, bound_k, fault, schedule_seed)` run.
+#[derive(Debug, Clone)]
+pub struct TestCaseResult {
+    pub run_id: String,
+    pub failure: Option<TestCaseFailure>,
+}
+
+/// Accumulates `TestCaseResult`s across a `run-matrix` sweep and writes them
+/// out as one `<testsuite>` of `<testcase>` elements.
+pub struct JunitReport {
+    suite_name: String,
+    cases: Vec<TestCaseResult>,
+}
+
+impl JunitReport {
+    pub fn new(suite_name: impl Into<String>) -> Self {
+        Self {
+            suite_name: suite_name.into(),
+            cases: Vec::new(),
+        }
+    }
+
+    /// Record one run's outcome.
+    pub fn record(&mut self, case: TestCaseResult) {
+        self.cases.push(case);
+    }
+
+    /// Write the accumulated cases as JUnit XML to `path`.
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        let tests = self.cases.len();
+        let failures = self.cases.iter().filter(|c| c.failure.is_some()).count();
+
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+            escape_xml(&self.suite_name),
+            tests,
+            failures
+        ));
+
+        for case in &self.cases {
+            out.push_str(&format!(
+                "  <testcase name=\"{}\" classname=\"{}\">\n",
+                escape_xml(&case.run_id),
+                escape_xml(&self.suite_name)
+            ));
+            if let Some(failure) = &case.failure {
+                out.push_str(&format!(
+                    "    <failure message=\"{}\">\n",
+                    escape_xml(&failure.message)
+                ));
+                if let Some(schedule_path) = &failure.schedule_path {
+                    out.push_str(&format!(
+                        "schedule: {}\n",
+                        escape_xml(&schedule_path.display().to_string())
+                    ));
+                }
+                for line in &failure.log_lines {
+                    out.push_str(&escape_xml(line));
+                    out.push('\n');
+                }
+                out.push_str("    </failure>\n");
+            }
+            out.push_str("  </testcase>\n");
+        }
+
+        out.push_str("</testsuite>\n");
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+        }
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("failed to create JUnit report: {}", path.display()))?;
+        file.write_all(out.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Escape the handful of characters that are special in XML text/attribute content.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_with_no_failures() {
+        let mut report = JunitReport::new("nvme-lite-oracle");
+        report.record(TestCaseResult {
+            run_id: "seed_001_FIFO_inf_0_NONE".to_string(),
+            failure: None,
+        });
+
+        let tmp = std::env::temp_dir().join("test_junit_no_failures.xml");
+        report.write_to_file(&tmp).unwrap();
+        let content = std::fs::read_to_string(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        assert!(content.contains("tests=\"1\" failures=\"0\""));
+        assert!(content.contains("name=\"seed_001_FIFO_inf_0_NONE\""));
+        assert!(!content.contains("<failure"));
+    }
+
+    #[test]
+    fn test_report_with_failure_includes_log_and_schedule_path() {
+        let mut report = JunitReport::new("nvme-lite-oracle");
+        report.record(TestCaseResult {
+            run_id: "seed_002_ADVERSARIAL_0_5_RESET".to_string(),
+            failure: Some(TestCaseFailure {
+                message: "fence ordering violation".to_string(),
+                log_lines: vec!["SUBMIT 0 WRITE".to_string(), "COMPLETE 0 OK".to_string()],
+                schedule_path: Some(PathBuf::from("out/schedules/seed_002.json")),
+            }),
+        });
+
+        let tmp = std::env::temp_dir().join("test_junit_with_failure.xml");
+        report.write_to_file(&tmp).unwrap();
+        let content = std::fs::read_to_string(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        assert!(content.contains("tests=\"1\" failures=\"1\""));
+        assert!(content.contains("message=\"fence ordering violation\""));
+        assert!(content.contains("SUBMIT 0 WRITE"));
+        assert!(content.contains("out/schedules/seed_002.json"));
+    }
+
+    #[test]
+    fn test_escape_xml_handles_special_characters() {
+        assert_eq!(escape_xml("a < b & c > \"d\""), "a &lt; b &amp; c &gt; &quot;d&quot;");
+    }
+}
@@ -0,0 +1,17 @@
+//! NVMe-lite Oracle library: modules shared by the `nvme-lite-oracle` binary
+//! and out-of-process consumers (fuzz targets, external tooling).
+
+pub mod config;
+pub mod failure_corpus;
+pub mod fuzz;
+pub mod gc;
+pub mod junit;
+pub mod logging;
+pub mod matrix_cache;
+pub mod metrics;
+pub mod model;
+pub mod multi_queue;
+pub mod quarantine;
+pub mod runner;
+pub mod scheduler;
+pub mod seed;
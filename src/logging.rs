@@ -47,7 +47,7 @@ impl std::fmt::Display for SubmitWindow {
 }
 
 /// Fault mode for a run
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum FaultMode {
     NONE,
     TIMEOUT,
@@ -210,10 +210,59 @@ pub struct SerializedSchedule {
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "type")]
 pub enum ScheduleStep {
+    /// A `next_bit()` call whose outcome was ambiguous (both submit and
+    /// complete were possible) and therefore had to be recorded to reproduce
+    /// the submit/complete interleaving during replay.
+    Bit { bit: u8 },
     CompletePick { pick_index: usize },
     FAULT { fault_type: String, at_step: usize },
 }
 
+/// Error parsing or replaying a `SerializedSchedule`.
+///
+/// Kept distinct from `anyhow::Error` so a corrupt or incompatible schedule
+/// file fails loudly with a specific reason instead of silently producing a
+/// garbage replay.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The schedule file could not be read from disk.
+    Io(std::io::Error),
+    /// The file isn't valid JSON, or is missing fields a schedule must have
+    /// (e.g. cut off mid-write).
+    Truncated(String),
+    /// A step's `type` tag isn't one this version of the replayer understands.
+    UnknownEventKind(String),
+    /// A recorded `pick_index` no longer fits the candidate window at replay
+    /// time (e.g. bound_k or the seed changed since the schedule was captured).
+    PickIndexOutOfRange { pick_index: usize, candidates: usize },
+    /// The replayer asked for a decision but the schedule had no more
+    /// recorded steps.
+    Exhausted,
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "failed to read schedule file: {}", e),
+            ReplayError::Truncated(msg) => write!(f, "malformed or truncated schedule: {}", msg),
+            ReplayError::UnknownEventKind(kind) => {
+                write!(f, "unknown schedule event kind: {}", kind)
+            }
+            ReplayError::PickIndexOutOfRange {
+                pick_index,
+                candidates,
+            } => write!(
+                f,
+                "recorded pick_index {} is out of range for {} candidate(s)",
+                pick_index, candidates
+            ),
+            ReplayError::Exhausted => write!(f, "schedule has no more recorded steps"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
 impl SerializedSchedule {
     pub fn new(
         seed_id: &str,
@@ -232,6 +281,10 @@ impl SerializedSchedule {
         }
     }
 
+    pub fn add_bit(&mut self, bit: u64) {
+        self.steps.push(ScheduleStep::Bit { bit: bit as u8 });
+    }
+
     pub fn add_complete(&mut self, pick_index: usize) {
         self.steps.push(ScheduleStep::CompletePick { pick_index });
     }
@@ -248,6 +301,34 @@ impl SerializedSchedule {
         std::fs::write(path, json)?;
         Ok(())
     }
+
+    /// Parse a schedule back from disk, failing loudly (rather than
+    /// silently succeeding on garbage) on malformed JSON, an unrecognized
+    /// step kind, or a truncated record.
+    pub fn load(path: &std::path::Path) -> std::result::Result<Self, ReplayError> {
+        let content = std::fs::read_to_string(path).map_err(ReplayError::Io)?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| ReplayError::Truncated(e.to_string()))?;
+
+        let steps = value
+            .get("steps")
+            .and_then(|s| s.as_array())
+            .ok_or_else(|| ReplayError::Truncated("missing `steps` array".to_string()))?;
+
+        for step in steps {
+            match step.get("type").and_then(|t| t.as_str()) {
+                Some("Bit") | Some("CompletePick") | Some("FAULT") => {}
+                Some(other) => return Err(ReplayError::UnknownEventKind(other.to_string())),
+                None => {
+                    return Err(ReplayError::Truncated(
+                        "step missing `type` field".to_string(),
+                    ))
+                }
+            }
+        }
+
+        serde_json::from_value(value).map_err(|e| ReplayError::Truncated(e.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -286,4 +367,45 @@ mod tests {
         assert!(log.contains("COMPLETE(cmd_id=0, status=OK, out=0)"));
         assert!(log.contains("RUN_END(pending_left=0, pending_peak=2)"));
     }
+
+    #[test]
+    fn test_schedule_load_roundtrip() {
+        let mut schedule =
+            SerializedSchedule::new("seed_001", 42, Policy::FIFO, BoundK::Infinite, FaultMode::NONE);
+        schedule.add_bit(1);
+        schedule.add_complete(0);
+        schedule.add_fault("RESET", 3);
+
+        let tmp = std::env::temp_dir().join("test_schedule_roundtrip.json");
+        schedule.write_to_file(&tmp).unwrap();
+
+        let loaded = SerializedSchedule::load(&tmp).unwrap();
+        assert_eq!(loaded.steps.len(), 3);
+    }
+
+    #[test]
+    fn test_schedule_load_rejects_unknown_event_kind() {
+        let tmp = std::env::temp_dir().join("test_schedule_bad_kind.json");
+        std::fs::write(
+            &tmp,
+            r#"{"seed_id":"s","schedule_seed":0,"policy":"FIFO","bound_k":"inf","fault_mode":"NONE","steps":[{"type":"BOGUS"}]}"#,
+        )
+        .unwrap();
+
+        match SerializedSchedule::load(&tmp) {
+            Err(ReplayError::UnknownEventKind(kind)) => assert_eq!(kind, "BOGUS"),
+            other => panic!("expected UnknownEventKind, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_schedule_load_rejects_truncated_json() {
+        let tmp = std::env::temp_dir().join("test_schedule_truncated.json");
+        std::fs::write(&tmp, r#"{"seed_id":"s","steps":["#).unwrap();
+
+        assert!(matches!(
+            SerializedSchedule::load(&tmp),
+            Err(ReplayError::Truncated(_))
+        ));
+    }
 }
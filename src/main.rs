@@ -2,7 +2,11 @@
 //!
 //! Usage:
 //!   nvme-lite-oracle run-one --seed-file seeds/seed_001.json --schedule-seed 42 ...
-//!   nvme-lite-oracle run-matrix --config configs/main.yaml --out-dir out/logs
+//!   nvme-lite-oracle run-matrix --config configs/main.yaml --out-dir out/logs --jobs 8 --shard 0/4
+//!   nvme-lite-oracle replay-schedule --seed-file seeds/seed_001.json --schedule-file out/schedules/x.json --out-log out/replay.log
+//!   nvme-lite-oracle replay --corpus out/failures.jsonl --out-log out/replayed
+//!   nvme-lite-oracle shrink --seed-file seeds/seed_001.json --schedule-seed 42 --policy ADVERSARIAL --bound-k 2 --out-seed out/seed_001.min.json
+//!   nvme-lite-oracle gc --out-dir out/logs --peak-threshold 8 --prune-older-than 7d
 //!
 //! Assumptions (design decisions):
 //! - run_id format: {seed_id}_{policy}_{bound_k}_{schedule_seed}_{fault_mode}
@@ -11,22 +15,28 @@
 //! - Fault injection happens at step n_cmds/2
 //! - RESET clears all pending; TIMEOUT affects one command
 
-mod config;
-mod logging;
-mod model;
-mod runner;
-mod scheduler;
-mod seed;
-
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-
-use config::ExperimentConfig;
-use logging::{FaultMode, SubmitWindow};
-use runner::{execute_run, RunConfig};
-use scheduler::{BoundK, Policy};
-use seed::Seed;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nvme_lite_oracle::config::ExperimentConfig;
+use nvme_lite_oracle::failure_corpus::{append_failure, load_corpus, FailureRecord};
+use nvme_lite_oracle::fuzz::{classify_violation, classify_violation_multi_queue, shrink, Violation};
+use nvme_lite_oracle::gc::{parse_duration, run_gc};
+use nvme_lite_oracle::junit::{JunitReport, TestCaseFailure, TestCaseResult};
+use nvme_lite_oracle::logging::{FaultMode, SubmitWindow};
+use nvme_lite_oracle::matrix_cache::MatrixIndex;
+use nvme_lite_oracle::metrics::{MetricsBuffer, MetricsKey, MetricsSink, MultiSink, PrometheusFileSink, StatsdSink};
+use nvme_lite_oracle::quarantine::{quarantine_if_violating, replay_quarantine, TriageOutcome};
+use nvme_lite_oracle::runner::{
+    diff_logs, execute_replay, execute_run, execute_run_multi_queue, execute_run_with_model,
+    RunConfig, RunResult,
+};
+use nvme_lite_oracle::scheduler::{BoundK, Policy};
+use nvme_lite_oracle::seed::Seed;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "nvme-lite-oracle")]
@@ -80,6 +90,16 @@ enum Commands {
         /// Dump schedule to JSON file
         #[arg(long)]
         dump_schedule: Option<PathBuf>,
+
+        /// Route the run through this many SQ/CQ queues instead of the
+        /// single-queue model (default: 1, the single-queue path; schedule
+        /// dumping is unavailable above 1)
+        #[arg(long, default_value = "1")]
+        num_queues: usize,
+
+        /// Per-queue ring-buffer depth, only consulted when --num-queues > 1
+        #[arg(long, default_value = "16")]
+        queue_depth: usize,
     },
 
     /// Run full experiment matrix from config
@@ -103,6 +123,140 @@ enum Commands {
         /// Dump schedules for all runs
         #[arg(long)]
         dump_schedules: bool,
+
+        /// Send aggregated metrics to this statsd UDP endpoint (host:port)
+        #[arg(long)]
+        statsd_addr: Option<String>,
+
+        /// Write aggregated metrics as Prometheus text-exposition to this file
+        #[arg(long)]
+        prometheus_out: Option<PathBuf>,
+
+        /// Flush aggregated metrics every N runs
+        #[arg(long, default_value = "100")]
+        metrics_flush_every: u64,
+
+        /// Write a JUnit XML report (one <testcase> per run) to this path
+        #[arg(long)]
+        junit_out: Option<PathBuf>,
+
+        /// Skip any run_id already completed (matching log on disk) per
+        /// `out_dir/.matrix-index.jsonl`
+        #[arg(long)]
+        resume: bool,
+
+        /// Ignore the result cache and re-run everything, even with --resume
+        #[arg(long)]
+        force: bool,
+
+        /// Run experiments across this many worker threads (default: 1, sequential)
+        #[arg(long, default_value = "1")]
+        jobs: usize,
+
+        /// Deterministically split the sweep across machines: "i/n" runs shard i of n (0-indexed)
+        #[arg(long)]
+        shard: Option<String>,
+
+        /// Quarantine any run that violates an oracle invariant (full
+        /// Seed/RunConfig/SerializedSchedule repro case) into this directory,
+        /// in addition to recording it in `failures.jsonl`
+        #[arg(long)]
+        quarantine_dir: Option<PathBuf>,
+    },
+
+    /// Replay a previously dumped SerializedSchedule verbatim, ignoring the
+    /// current scheduler's RNG/policy internals entirely
+    ReplaySchedule {
+        /// Path to seed file (JSON)
+        #[arg(long)]
+        seed_file: PathBuf,
+
+        /// Path to a schedule JSON file previously written via --dump-schedule
+        #[arg(long)]
+        schedule_file: PathBuf,
+
+        /// Output log file path
+        #[arg(long)]
+        out_log: PathBuf,
+
+        /// Compare the replayed log against a reference log from the
+        /// original run (e.g. the `.log` file `run-matrix --dump-schedules`
+        /// wrote alongside this schedule) and report the first diverging
+        /// line, if any
+        #[arg(long)]
+        reference_log: Option<PathBuf>,
+    },
+
+    /// Re-run every failure recorded in a `failures.jsonl` corpus from a
+    /// prior `run-matrix` sweep
+    Replay {
+        /// Path to the failure corpus (e.g. out/failures.jsonl)
+        #[arg(long)]
+        corpus: PathBuf,
+
+        /// Output directory for replayed logs (one `<run_id>.log` per failure)
+        #[arg(long)]
+        out_log: PathBuf,
+    },
+
+    /// Re-triage every case in a `quarantine/` directory: confirm it still
+    /// reproduces (moved to `confirmed/`) or drop it if it no longer does
+    ReplayQuarantine {
+        /// Path to the quarantine directory (e.g. out/quarantine)
+        #[arg(long)]
+        quarantine_dir: PathBuf,
+    },
+
+    /// Shrink a seed known to trip the oracle to a minimal reproducing
+    /// subsequence (ddmin)
+    Shrink {
+        /// Path to seed file (JSON)
+        #[arg(long)]
+        seed_file: PathBuf,
+
+        /// Schedule seed (RNG seed for scheduling decisions)
+        #[arg(long)]
+        schedule_seed: u64,
+
+        /// Scheduling policy: FIFO, RANDOM, ADVERSARIAL, BATCHED
+        #[arg(long)]
+        policy: String,
+
+        /// Reorder bound: 0, 1, 2, ... or "inf"
+        #[arg(long)]
+        bound_k: String,
+
+        /// Fault mode: NONE, TIMEOUT, RESET
+        #[arg(long, default_value = "NONE")]
+        fault_mode: String,
+
+        /// Path to write the minimized seed JSON
+        #[arg(long)]
+        out_seed: PathBuf,
+    },
+
+    /// Compact a run-matrix output directory: bundle clean ("cruft") logs
+    /// into a single pack file, leaving interesting logs on disk
+    Gc {
+        /// Directory of .log files from a prior run-matrix sweep
+        #[arg(long)]
+        out_dir: PathBuf,
+
+        /// Treat pending_peak at or above this value as interesting (0 disables the check)
+        #[arg(long, default_value = "0")]
+        peak_threshold: u32,
+
+        /// Only prune logs at least this old, e.g. "24h", "7d" (unset: age-independent)
+        #[arg(long)]
+        prune_older_than: Option<String>,
+
+        /// Skip entirely unless out_dir holds more than this many .log files
+        #[arg(long)]
+        auto_threshold: Option<usize>,
+
+        /// Report what would be pruned without modifying anything
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
@@ -121,6 +275,8 @@ fn main() -> Result<()> {
             scheduler_version,
             git_commit,
             dump_schedule,
+            num_queues,
+            queue_depth,
         } => run_one(
             &seed_file,
             schedule_seed,
@@ -132,6 +288,8 @@ fn main() -> Result<()> {
             &scheduler_version,
             &git_commit,
             dump_schedule.as_deref(),
+            num_queues,
+            queue_depth,
         ),
 
         Commands::RunMatrix {
@@ -140,27 +298,82 @@ fn main() -> Result<()> {
             schedule_seeds,
             submit_window,
             dump_schedules,
+            statsd_addr,
+            prometheus_out,
+            metrics_flush_every,
+            junit_out,
+            resume,
+            force,
+            jobs,
+            shard,
+            quarantine_dir,
         } => run_matrix(
             &config,
             &out_dir,
             schedule_seeds.as_deref(),
             &submit_window,
             dump_schedules,
+            statsd_addr.as_deref(),
+            prometheus_out.as_deref(),
+            metrics_flush_every,
+            junit_out.as_deref(),
+            resume,
+            force,
+            jobs,
+            shard.as_deref(),
+            quarantine_dir.as_deref(),
+        ),
+
+        Commands::ReplaySchedule {
+            seed_file,
+            schedule_file,
+            out_log,
+            reference_log,
+        } => replay_schedule(&seed_file, &schedule_file, &out_log, reference_log.as_deref()),
+
+        Commands::Replay { corpus, out_log } => replay_corpus(&corpus, &out_log),
+
+        Commands::ReplayQuarantine { quarantine_dir } => replay_quarantine_dir(&quarantine_dir),
+
+        Commands::Shrink {
+            seed_file,
+            schedule_seed,
+            policy,
+            bound_k,
+            fault_mode,
+            out_seed,
+        } => shrink_seed(&seed_file, schedule_seed, &policy, &bound_k, &fault_mode, &out_seed),
+
+        Commands::Gc {
+            out_dir,
+            peak_threshold,
+            prune_older_than,
+            auto_threshold,
+            dry_run,
+        } => gc(
+            &out_dir,
+            peak_threshold,
+            prune_older_than.as_deref(),
+            auto_threshold,
+            dry_run,
         ),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_one(
-    seed_file: &PathBuf,
+    seed_file: &Path,
     schedule_seed: u64,
     policy: &str,
     bound_k: &str,
     fault_mode: &str,
     submit_window: &str,
-    out_log: &PathBuf,
+    out_log: &Path,
     scheduler_version: &str,
     git_commit: &str,
     dump_schedule: Option<&std::path::Path>,
+    num_queues: usize,
+    queue_depth: usize,
 ) -> Result<()> {
     // Load seed
     let seed = Seed::load(seed_file)?;
@@ -188,8 +401,19 @@ fn run_one(
         std::fs::create_dir_all(parent)?;
     }
 
-    // Execute run
-    let result = execute_run(&seed, &config, out_log, dump_schedule)?;
+    // Execute run. Above a single queue, route through the multi-queue
+    // model instead; schedule dumping is a single-queue-only feature, so
+    // --dump-schedule is ignored once --num-queues > 1.
+    let result = if num_queues > 1 {
+        if dump_schedule.is_some() {
+            eprintln!("Warning: --dump-schedule is ignored when --num-queues > 1");
+        }
+        let (result, _model) =
+            execute_run_multi_queue(&seed, &config, num_queues, queue_depth, out_log)?;
+        result
+    } else {
+        execute_run(&seed, &config, out_log, dump_schedule)?
+    };
 
     println!("Run completed: {}", result.run_id);
     println!("  pending_left: {}", result.pending_left);
@@ -197,16 +421,215 @@ fn run_one(
     if result.had_reset {
         println!("  commands_lost: {}", result.commands_lost);
     }
+    if !result.queue_peaks.is_empty() {
+        println!("  queue_peaks: {:?}", result.queue_peaks);
+        println!(
+            "  cross_queue_fence_violations: {}",
+            result.cross_queue_fence_violations
+        );
+    }
 
     Ok(())
 }
 
+fn replay_schedule(
+    seed_file: &Path,
+    schedule_file: &Path,
+    out_log: &Path,
+    reference_log: Option<&Path>,
+) -> Result<()> {
+    let seed = Seed::load(seed_file)?;
+
+    if let Some(parent) = out_log.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let result = execute_replay(&seed, schedule_file, out_log)?;
+
+    println!("Replay completed: {}", result.run_id);
+    println!("  pending_left: {}", result.pending_left);
+    println!("  pending_peak: {}", result.pending_peak);
+    if result.had_reset {
+        println!("  commands_lost: {}", result.commands_lost);
+    }
+
+    if let Some(reference) = reference_log {
+        match diff_logs(reference, out_log, true)? {
+            None => println!("  matches reference log (body identical)"),
+            Some((line, expected, actual)) => {
+                anyhow::bail!(
+                    "replay diverges from reference log {} at line {}:\n  expected: {}\n  actual:   {}",
+                    reference.display(),
+                    line,
+                    expected,
+                    actual
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn shrink_seed(
+    seed_file: &Path,
+    schedule_seed: u64,
+    policy: &str,
+    bound_k: &str,
+    fault_mode: &str,
+    out_seed: &Path,
+) -> Result<()> {
+    let seed = Seed::load(seed_file)?;
+
+    let policy: Policy = policy.parse().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let bound_k = BoundK::parse(bound_k).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let fault_mode: FaultMode = fault_mode.parse().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let config = RunConfig {
+        seed_id: seed.seed_id.clone(),
+        schedule_seed,
+        policy,
+        bound_k,
+        fault_mode,
+        submit_window: SubmitWindow::Infinite,
+        scheduler_version: "v1.0".to_string(),
+        git_commit: String::new(),
+        dump_schedule: false,
+    };
+
+    match shrink(&seed, &config)? {
+        Some(minimized) => {
+            if let Some(parent) = out_seed.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(out_seed, serde_json::to_string_pretty(&minimized)?)?;
+            println!(
+                "Shrunk {} command(s) to {} command(s), written to {}",
+                seed.commands.len(),
+                minimized.commands.len(),
+                out_seed.display()
+            );
+        }
+        None => {
+            println!(
+                "Seed {} does not reproduce a violation under this config; nothing to shrink",
+                seed_file.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn gc(
+    out_dir: &Path,
+    peak_threshold: u32,
+    prune_older_than: Option<&str>,
+    auto_threshold: Option<usize>,
+    dry_run: bool,
+) -> Result<()> {
+    let older_than = prune_older_than.map(parse_duration).transpose()?;
+
+    let report = run_gc(out_dir, peak_threshold, older_than, auto_threshold, dry_run)?;
+
+    if report.interesting == 0 && report.cruft == 0 {
+        println!("Nothing to do (below --auto-threshold or no matching logs)");
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would pack" } else { "Packed" };
+    println!("Interesting (kept): {}", report.interesting);
+    println!(
+        "{} {} cruft log(s), {} byte(s)",
+        verb, report.cruft, report.packed_bytes
+    );
+
+    Ok(())
+}
+
+fn replay_corpus(corpus: &Path, out_log: &Path) -> Result<()> {
+    let records = load_corpus(corpus)?;
+    std::fs::create_dir_all(out_log)?;
+
+    println!("Replaying {} recorded failure(s)...", records.len());
+    let mut errors = 0;
+    for record in &records {
+        let (seed, config) = match record.load() {
+            Ok(pair) => pair,
+            Err(e) => {
+                eprintln!("Error loading failure {}: {}", record.run_id, e);
+                errors += 1;
+                continue;
+            }
+        };
+
+        let log_path = out_log.join(format!("{}.log", config.run_id()));
+        match execute_run(&seed, &config, &log_path, None) {
+            Ok(result) => {
+                println!(
+                    "  {}: pending_left={} pending_peak={}",
+                    result.run_id, result.pending_left, result.pending_peak
+                );
+            }
+            Err(e) => {
+                eprintln!("Error replaying {}: {}", record.run_id, e);
+                errors += 1;
+            }
+        }
+    }
+
+    println!("\nReplayed: {}/{}", records.len() - errors, records.len());
+    if errors > 0 {
+        println!("Errors: {}", errors);
+    }
+
+    Ok(())
+}
+
+fn replay_quarantine_dir(quarantine_dir: &Path) -> Result<()> {
+    let outcomes = replay_quarantine(quarantine_dir)?;
+
+    let mut confirmed = 0;
+    let mut dropped = 0;
+    for (path, outcome) in &outcomes {
+        match outcome {
+            TriageOutcome::Deterministic => {
+                confirmed += 1;
+                println!("  {}: confirmed", path.display());
+            }
+            TriageOutcome::NoLongerReproduces => {
+                dropped += 1;
+                println!("  {}: no longer reproduces (dropped)", path.display());
+            }
+        }
+    }
+
+    println!(
+        "\nTriaged {} case(s): {} confirmed, {} dropped",
+        outcomes.len(),
+        confirmed,
+        dropped
+    );
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn run_matrix(
-    config_path: &PathBuf,
-    out_dir: &PathBuf,
+    config_path: &Path,
+    out_dir: &Path,
     schedule_seeds_override: Option<&str>,
     submit_window_str: &str,
     dump_schedules: bool,
+    statsd_addr: Option<&str>,
+    prometheus_out: Option<&std::path::Path>,
+    metrics_flush_every: u64,
+    junit_out: Option<&std::path::Path>,
+    resume: bool,
+    force: bool,
+    jobs: usize,
+    shard: Option<&str>,
+    quarantine_dir: Option<&Path>,
 ) -> Result<()> {
     // Load config
     let mut config = ExperimentConfig::load(config_path)?;
@@ -221,6 +644,8 @@ fn run_matrix(
         config.schedule_seed_range = (start, end);
     }
 
+    let shard = shard.map(parse_shard).transpose()?;
+
     // Create output directories
     std::fs::create_dir_all(out_dir)?;
     if dump_schedules {
@@ -238,17 +663,52 @@ fn run_matrix(
         config.schedule_seed_range.0, config.schedule_seed_range.1
     );
     println!("  Submit window: {}", submit_window);
+    if config.num_queues > 1 {
+        println!(
+            "  Queues: {} (depth {})",
+            config.num_queues, config.queue_depth
+        );
+    }
+    println!("  Jobs: {}", jobs.max(1));
+    if let Some((shard_index, shard_count)) = shard {
+        println!("  Shard: {}/{}", shard_index, shard_count);
+    }
 
-    let mut completed = 0;
-    let mut errors = 0;
+    // Resume support: only consult the cache to skip runs when --resume is
+    // set and --force hasn't overridden it, but always keep the index itself
+    // up to date so a later --resume run benefits.
+    let use_cache = resume && !force;
+    let index = Mutex::new(MatrixIndex::load(out_dir)?);
 
-    // Iterate through all combinations
+    // Wire up metrics export, if requested
+    let mut sinks: Vec<Box<dyn MetricsSink>> = Vec::new();
+    if let Some(addr) = statsd_addr {
+        sinks.push(Box::new(StatsdSink::new(addr)?));
+    }
+    if let Some(path) = prometheus_out {
+        sinks.push(Box::new(PrometheusFileSink::new(path)));
+    }
+    let metrics_sink: Option<MultiSink> = if sinks.is_empty() {
+        None
+    } else {
+        Some(MultiSink::new(sinks))
+    };
+    let metrics = MetricsBuffer::new(metrics_flush_every, Duration::from_secs(10));
+    let junit = junit_out.map(|_| JunitReport::new("nvme-lite-oracle"));
+
+    // Build the full (seed, policy, bound_k, fault_mode, schedule_seed) tuple
+    // list up front so it can be dispatched across a worker pool and
+    // deterministically sharded by index; each tuple is independent and
+    // carries its own schedule_seed, so dispatch order doesn't affect a
+    // job's result, only which thread/machine produces it.
+    let errors = AtomicU64::new(0);
+    let mut jobs_list: Vec<MatrixJob> = Vec::new();
     for seed_path in &config.seeds.clone() {
         let seed = match Seed::load(std::path::Path::new(seed_path)) {
-            Ok(s) => s,
+            Ok(s) => Arc::new(s),
             Err(e) => {
                 eprintln!("Error loading seed {}: {}", seed_path, e);
-                errors += 1;
+                errors.fetch_add(1, Ordering::Relaxed);
                 continue;
             }
         };
@@ -257,45 +717,201 @@ fn run_matrix(
             for &bound_k in &config.bounds {
                 for &fault_mode in &config.faults {
                     for schedule_seed in config.schedule_seeds() {
-                        let run_config = RunConfig {
-                            seed_id: seed.seed_id.clone(),
-                            schedule_seed,
+                        jobs_list.push(MatrixJob {
+                            seed: seed.clone(),
+                            seed_path: seed_path.clone(),
                             policy,
                             bound_k,
                             fault_mode,
-                            submit_window,
-                            scheduler_version: config.scheduler_version.clone(),
-                            git_commit: config.git_commit.clone(),
-                            dump_schedule: dump_schedules,
-                        };
-
-                        let run_id = run_config.run_id();
-                        let log_path = out_dir.join(format!("{}.log", run_id));
-                        let schedule_path = if dump_schedules {
-                            Some(out_dir.join("schedules").join(format!("{}.json", run_id)))
-                        } else {
-                            None
-                        };
-
-                        match execute_run(&seed, &run_config, &log_path, schedule_path.as_deref()) {
-                            Ok(_) => {
-                                completed += 1;
-                                if completed % 100 == 0 {
-                                    println!("Progress: {}/{}", completed, total);
+                            schedule_seed,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some((shard_index, shard_count)) = shard {
+        jobs_list = jobs_list
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % shard_count == shard_index)
+            .map(|(_, job)| job)
+            .collect();
+        println!("  Assigned to this shard: {}", jobs_list.len());
+    }
+
+    // Completed/skipped counters and the metrics/junit/index state are
+    // shared across worker threads, so they move behind atomics/mutexes
+    // instead of the plain counters and `&mut` state a single-threaded loop
+    // could use.
+    let completed = AtomicU64::new(0);
+    let skipped = AtomicU64::new(0);
+    let metrics_state = Mutex::new((metrics_sink, metrics));
+    let junit_state = Mutex::new(junit);
+    let corpus_lock = Mutex::new(());
+    let next_job = AtomicUsize::new(0);
+
+    let num_workers = jobs.max(1).min(jobs_list.len().max(1));
+    std::thread::scope(|scope| {
+        for _ in 0..num_workers {
+            scope.spawn(|| loop {
+                let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs_list.get(idx) else {
+                    break;
+                };
+
+                let run_config = RunConfig {
+                    seed_id: job.seed.seed_id.clone(),
+                    schedule_seed: job.schedule_seed,
+                    policy: job.policy,
+                    bound_k: job.bound_k,
+                    fault_mode: job.fault_mode,
+                    submit_window,
+                    scheduler_version: config.scheduler_version.clone(),
+                    git_commit: config.git_commit.clone(),
+                    dump_schedule: dump_schedules,
+                };
+
+                let run_id = run_config.run_id();
+                let log_path = out_dir.join(format!("{}.log", run_id));
+                let schedule_path = if dump_schedules {
+                    Some(out_dir.join("schedules").join(format!("{}.json", run_id)))
+                } else {
+                    None
+                };
+
+                if use_cache && index.lock().unwrap().is_complete(&run_id, &log_path) {
+                    skipped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+
+                // Above a single queue, run the sweep through the multi-queue
+                // model instead so `RunResult.queue_peaks` /
+                // `cross_queue_fence_violations` and the metrics sink's
+                // `queue_peak_max` aggregation are actually populated;
+                // schedule dumping is single-queue-only and is skipped here.
+                let outcome: Result<(RunResult, Option<Violation>, bool)> = if config.num_queues > 1
+                {
+                    execute_run_multi_queue(
+                        &job.seed,
+                        &run_config,
+                        config.num_queues,
+                        config.queue_depth,
+                        &log_path,
+                    )
+                    .map(|(result, model)| {
+                        let fence_violation = model.check_fence_invariant().is_some();
+                        let violation = classify_violation_multi_queue(&result, &model);
+                        (result, violation, fence_violation)
+                    })
+                } else {
+                    execute_run_with_model(
+                        &job.seed,
+                        &run_config,
+                        &log_path,
+                        schedule_path.as_deref(),
+                    )
+                    .map(|(result, model)| {
+                        let fence_violation = model.check_fence_invariant().is_some();
+                        let violation = classify_violation(&result, &model);
+                        (result, violation, fence_violation)
+                    })
+                };
+
+                match outcome {
+                    Ok((result, violation, fence_violation)) => {
+                        {
+                            let mut state = metrics_state.lock().unwrap();
+                            let (sink, metrics) = &mut *state;
+                            if let Some(sink) = sink.as_mut() {
+                                let key = MetricsKey {
+                                    policy: job.policy,
+                                    bound_k: job.bound_k,
+                                    fault_mode: job.fault_mode,
+                                };
+                                if let Err(e) = metrics.record(key, &result, fence_violation, sink)
+                                {
+                                    eprintln!("Error recording metrics for {}: {}", run_id, e);
                                 }
                             }
-                            Err(e) => {
-                                eprintln!("Error in run {}: {}", run_id, e);
-                                errors += 1;
+                        }
+                        if let Some(v) = &violation {
+                            let record = FailureRecord::new(
+                                &job.seed_path,
+                                &run_config,
+                                submit_window_str,
+                                v.to_string(),
+                            );
+                            {
+                                let _guard = corpus_lock.lock().unwrap();
+                                if let Err(e) =
+                                    append_failure(&out_dir.join("failures.jsonl"), &record)
+                                {
+                                    eprintln!(
+                                        "Error recording failure {} to corpus: {}",
+                                        run_id, e
+                                    );
+                                }
                             }
+                            if let Some(dir) = quarantine_dir {
+                                if let Err(e) =
+                                    quarantine_if_violating(&job.seed, &run_config, dir)
+                                {
+                                    eprintln!(
+                                        "Error quarantining failure {}: {}",
+                                        run_id, e
+                                    );
+                                }
+                            }
+                        }
+                        if let Some(report) = junit_state.lock().unwrap().as_mut() {
+                            let failure = violation.map(|v| TestCaseFailure {
+                                message: v.to_string(),
+                                log_lines: std::fs::read_to_string(&log_path)
+                                    .map(|s| s.lines().map(str::to_string).collect())
+                                    .unwrap_or_default(),
+                                schedule_path: schedule_path.clone(),
+                            });
+                            report.record(TestCaseResult {
+                                run_id: run_id.clone(),
+                                failure,
+                            });
+                        }
+                        if let Err(e) = index.lock().unwrap().record(out_dir, &run_id, &log_path) {
+                            eprintln!("Error recording matrix index for {}: {}", run_id, e);
                         }
+                        let n = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                        if n.is_multiple_of(100) {
+                            println!("Progress: {}/{}", n, total);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Error in run {}: {}", run_id, e);
+                        errors.fetch_add(1, Ordering::Relaxed);
                     }
                 }
-            }
+            });
         }
+    });
+
+    let (mut metrics_sink, mut metrics) = metrics_state.into_inner().unwrap();
+    if let Some(sink) = metrics_sink.as_mut() {
+        metrics.flush(sink)?;
     }
+    let junit = junit_state.into_inner().unwrap();
+    if let Some(report) = &junit {
+        report.write_to_file(junit_out.expect("junit_out set whenever junit is Some"))?;
+    }
+
+    let completed = completed.into_inner();
+    let skipped = skipped.into_inner();
+    let errors = errors.into_inner();
 
     println!("\nCompleted: {}/{}", completed, total);
+    if skipped > 0 {
+        println!("Skipped (cached): {}", skipped);
+    }
     if errors > 0 {
         println!("Errors: {}", errors);
     }
@@ -303,6 +919,35 @@ fn run_matrix(
     Ok(())
 }
 
+/// One independent unit of work in a matrix sweep: a seed (shared via `Arc`
+/// across every policy/bound/fault/schedule_seed combination drawn from it)
+/// plus the resolved knobs for one run.
+struct MatrixJob {
+    seed: Arc<Seed>,
+    seed_path: String,
+    policy: Policy,
+    bound_k: BoundK,
+    fault_mode: FaultMode,
+    schedule_seed: u64,
+}
+
+/// Parse a shard spec like "0/4" (shard index 0 of 4) into `(index, count)`.
+fn parse_shard(s: &str) -> Result<(usize, usize)> {
+    let (index, count) = s
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("invalid shard spec: {} (expected \"i/n\")", s))?;
+    let index: usize = index.parse().context("invalid shard index")?;
+    let count: usize = count.parse().context("invalid shard count")?;
+    if count == 0 || index >= count {
+        return Err(anyhow::anyhow!(
+            "invalid shard spec: {} (index must be < count, count must be > 0)",
+            s
+        ));
+    }
+    Ok((index, count))
+}
+
+
 /// Parse a range string like "0-99" or "42"
 fn parse_range(s: &str) -> Result<(u64, u64)> {
     if let Some((start, end)) = s.split_once('-') {
@@ -0,0 +1,140 @@
+//! Resumable result cache for `run-matrix`, keyed on `RunConfig::run_id()`
+//! (inspired by proptest's `result_cache`).
+//!
+//! Every completed run appends one line to `out_dir/.matrix-index.jsonl`
+//! recording its `run_id` and a content hash of its log file. `--resume`
+//! loads this index up front and skips any `run_id` whose log still exists
+//! on disk with a matching hash, so a crashed or partial sweep can be
+//! restarted without re-running work that already completed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::Path;
+
+/// The name of the index file, relative to `run_matrix`'s `out_dir`.
+pub const INDEX_FILE_NAME: &str = ".matrix-index.jsonl";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexEntry {
+    run_id: String,
+    log_hash: u64,
+}
+
+/// `run_id -> log_hash` for every run recorded in the index so far.
+#[derive(Debug, Default)]
+pub struct MatrixIndex {
+    entries: HashMap<String, u64>,
+}
+
+impl MatrixIndex {
+    /// Load the index from `out_dir/.matrix-index.jsonl`, or an empty index
+    /// if the file doesn't exist yet.
+    pub fn load(out_dir: &Path) -> Result<Self> {
+        let path = out_dir.join(INDEX_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read matrix index: {}", path.display()))?;
+        let mut entries = HashMap::new();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let entry: IndexEntry = serde_json::from_str(line)
+                .with_context(|| format!("malformed matrix index entry: {}", line))?;
+            entries.insert(entry.run_id, entry.log_hash);
+        }
+        Ok(Self { entries })
+    }
+
+    /// True if `run_id` is recorded with a hash matching `log_path`'s
+    /// current on-disk content (so a truncated/corrupted log from a crash
+    /// mid-write is correctly treated as not completed).
+    pub fn is_complete(&self, run_id: &str, log_path: &Path) -> bool {
+        let Some(&recorded_hash) = self.entries.get(run_id) else {
+            return false;
+        };
+        match hash_file(log_path) {
+            Ok(hash) => hash == recorded_hash,
+            Err(_) => false,
+        }
+    }
+
+    /// Record `run_id` as completed and append it to the on-disk index,
+    /// hashing `log_path`'s current content.
+    pub fn record(&mut self, out_dir: &Path, run_id: &str, log_path: &Path) -> Result<()> {
+        let log_hash = hash_file(log_path)?;
+        self.entries.insert(run_id.to_string(), log_hash);
+
+        let path = out_dir.join(INDEX_FILE_NAME);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open matrix index: {}", path.display()))?;
+        let entry = IndexEntry {
+            run_id: run_id.to_string(),
+            log_hash,
+        };
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Hash a log file's content with `DefaultHasher`; cheap and sufficient for
+/// detecting a truncated or stale log, not a cryptographic integrity check.
+fn hash_file(path: &Path) -> Result<u64> {
+    let content = std::fs::read(path)
+        .with_context(|| format!("failed to read log for hashing: {}", path.display()))?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_skips_matching_log() {
+        let dir = std::env::temp_dir().join("test_matrix_cache_resume");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::remove_file(dir.join(INDEX_FILE_NAME)).ok();
+
+        let log_path = dir.join("run_a.log");
+        std::fs::write(&log_path, b"some deterministic log content").unwrap();
+
+        let mut index = MatrixIndex::load(&dir).unwrap();
+        assert!(!index.is_complete("run_a", &log_path));
+
+        index.record(&dir, "run_a", &log_path).unwrap();
+        assert!(index.is_complete("run_a", &log_path));
+
+        let reloaded = MatrixIndex::load(&dir).unwrap();
+        assert!(reloaded.is_complete("run_a", &log_path));
+
+        std::fs::remove_file(&log_path).ok();
+        std::fs::remove_file(dir.join(INDEX_FILE_NAME)).ok();
+    }
+
+    #[test]
+    fn test_changed_log_is_not_complete() {
+        let dir = std::env::temp_dir().join("test_matrix_cache_changed");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::remove_file(dir.join(INDEX_FILE_NAME)).ok();
+
+        let log_path = dir.join("run_b.log");
+        std::fs::write(&log_path, b"first content").unwrap();
+
+        let mut index = MatrixIndex::load(&dir).unwrap();
+        index.record(&dir, "run_b", &log_path).unwrap();
+
+        std::fs::write(&log_path, b"truncated").unwrap();
+        assert!(!index.is_complete("run_b", &log_path));
+
+        std::fs::remove_file(&log_path).ok();
+        std::fs::remove_file(dir.join(INDEX_FILE_NAME)).ok();
+    }
+}
@@ -0,0 +1,448 @@
+//! Metrics aggregation: buffer `RunResult`s across a sweep and export them to
+//! a pluggable sink (statsd, Prometheus) instead of post-processing log files.
+
+use crate::logging::FaultMode;
+use crate::runner::RunResult;
+use crate::scheduler::{BoundK, Policy};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Identifies one `(policy, bound_k, fault_mode)` bucket in a sweep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricsKey {
+    pub policy: Policy,
+    pub bound_k: BoundK,
+    pub fault_mode: FaultMode,
+}
+
+impl std::fmt::Display for MetricsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}_{}_{}", self.policy, self.bound_k, self.fault_mode)
+    }
+}
+
+/// Accumulated counters and a pending_peak histogram for one bucket.
+#[derive(Debug, Default, Clone)]
+pub struct BucketStats {
+    pub total_runs: u64,
+    pub runs_with_reset: u64,
+    pub commands_lost: u64,
+    pub fence_violations: u64,
+    /// Sum of `RunResult::cross_queue_fence_violations` across the bucket's
+    /// runs; 0 for buckets fed only single-queue runs.
+    pub cross_queue_fence_violations: u64,
+    pending_peak_histogram: HashMap<u32, u64>,
+    /// Highest `queue_peaks` value seen across all multi-queue runs in this
+    /// bucket, per queue index. Empty for buckets fed only single-queue runs.
+    queue_peak_max: Vec<u32>,
+}
+
+impl BucketStats {
+    fn record(&mut self, result: &RunResult, fence_violation: bool) {
+        self.total_runs += 1;
+        if result.had_reset {
+            self.runs_with_reset += 1;
+        }
+        self.commands_lost += result.commands_lost as u64;
+        if fence_violation {
+            self.fence_violations += 1;
+        }
+        self.cross_queue_fence_violations += result.cross_queue_fence_violations as u64;
+        *self
+            .pending_peak_histogram
+            .entry(result.pending_peak)
+            .or_insert(0) += 1;
+
+        if self.queue_peak_max.len() < result.queue_peaks.len() {
+            self.queue_peak_max.resize(result.queue_peaks.len(), 0);
+        }
+        for (slot, &peak) in self.queue_peak_max.iter_mut().zip(&result.queue_peaks) {
+            *slot = std::cmp::max(*slot, peak);
+        }
+    }
+
+    pub fn pending_peak_histogram(&self) -> &HashMap<u32, u64> {
+        &self.pending_peak_histogram
+    }
+
+    /// Highest per-queue pending depth seen, indexed by queue index.
+    pub fn queue_peak_max(&self) -> &[u32] {
+        &self.queue_peak_max
+    }
+}
+
+/// A destination for aggregated metrics. Implementations translate the
+/// generic gauge/counter/timing calls into their wire format.
+pub trait MetricsSink: Send {
+    fn gauge(&mut self, name: &str, value: f64) -> Result<()>;
+    fn counter(&mut self, name: &str, value: u64) -> Result<()>;
+    fn timing(&mut self, name: &str, millis: u64) -> Result<()>;
+    /// Called once after a batch of gauge/counter/timing calls; sinks that
+    /// batch writes (e.g. to a file) should commit them here.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Line-oriented statsd UDP sink: `name:value|g`, `name:value|c`, `name:value|ms`.
+pub struct StatsdSink {
+    socket: std::net::UdpSocket,
+    addr: std::net::SocketAddr,
+}
+
+impl StatsdSink {
+    pub fn new(addr: &str) -> Result<Self> {
+        let socket = std::net::UdpSocket::bind("0.0.0.0:0")?;
+        let addr = addr.parse()?;
+        Ok(Self { socket, addr })
+    }
+
+    fn send(&self, line: &str) -> Result<()> {
+        self.socket.send_to(line.as_bytes(), self.addr)?;
+        Ok(())
+    }
+}
+
+impl MetricsSink for StatsdSink {
+    fn gauge(&mut self, name: &str, value: f64) -> Result<()> {
+        self.send(&format!("{}:{}|g", name, value))
+    }
+
+    fn counter(&mut self, name: &str, value: u64) -> Result<()> {
+        self.send(&format!("{}:{}|c", name, value))
+    }
+
+    fn timing(&mut self, name: &str, millis: u64) -> Result<()> {
+        self.send(&format!("{}:{}|ms", name, millis))
+    }
+}
+
+/// Prometheus text-exposition sink: buffers `# TYPE`/metric lines in memory
+/// and writes them to `path` as a single file on `flush`.
+pub struct PrometheusFileSink {
+    path: std::path::PathBuf,
+    lines: Vec<String>,
+    seen_types: std::collections::HashSet<String>,
+}
+
+impl PrometheusFileSink {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lines: Vec::new(),
+            seen_types: std::collections::HashSet::new(),
+        }
+    }
+
+    fn push(&mut self, name: &str, kind: &str, value: f64) {
+        let name = sanitize_prometheus_name(name);
+        if self.seen_types.insert(name.clone()) {
+            self.lines.push(format!("# TYPE {} {}", name, kind));
+        }
+        self.lines.push(format!("{} {}", name, value));
+    }
+}
+
+/// Rewrite a metric name so it matches Prometheus's exposition format
+/// (`[a-zA-Z_:][a-zA-Z0-9_:]*`). Our keys are built as dot-joined segments
+/// (e.g. `nvme_lite.BATCHED_2_NONE.total_runs`) for statsd, where dots are
+/// the conventional separator, but a real Prometheus scraper rejects them —
+/// so any character outside that set becomes `_`, and a leading digit gets a
+/// `_` prefix.
+fn sanitize_prometheus_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+impl MetricsSink for PrometheusFileSink {
+    fn gauge(&mut self, name: &str, value: f64) -> Result<()> {
+        self.push(name, "gauge", value);
+        Ok(())
+    }
+
+    fn counter(&mut self, name: &str, value: u64) -> Result<()> {
+        self.push(name, "counter", value as f64);
+        Ok(())
+    }
+
+    fn timing(&mut self, name: &str, millis: u64) -> Result<()> {
+        self.push(&format!("{}_milliseconds", name), "gauge", millis as f64);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        std::fs::write(&self.path, self.lines.join("\n") + "\n")?;
+        Ok(())
+    }
+}
+
+/// Fans a single set of gauge/counter/timing/flush calls out to every
+/// wrapped sink, so a sweep can export to statsd and Prometheus at once.
+pub struct MultiSink {
+    sinks: Vec<Box<dyn MetricsSink>>,
+}
+
+impl MultiSink {
+    pub fn new(sinks: Vec<Box<dyn MetricsSink>>) -> Self {
+        Self { sinks }
+    }
+}
+
+impl MetricsSink for MultiSink {
+    fn gauge(&mut self, name: &str, value: f64) -> Result<()> {
+        for sink in &mut self.sinks {
+            sink.gauge(name, value)?;
+        }
+        Ok(())
+    }
+
+    fn counter(&mut self, name: &str, value: u64) -> Result<()> {
+        for sink in &mut self.sinks {
+            sink.counter(name, value)?;
+        }
+        Ok(())
+    }
+
+    fn timing(&mut self, name: &str, millis: u64) -> Result<()> {
+        for sink in &mut self.sinks {
+            sink.timing(name, millis)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        for sink in &mut self.sinks {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Buffers `RunResult`s keyed by `(policy, bound_k, fault_mode)` and flushes
+/// the aggregate to a `MetricsSink` every `flush_every_n` runs or every
+/// `flush_every` elapsed, whichever comes first.
+pub struct MetricsBuffer {
+    buckets: HashMap<MetricsKey, BucketStats>,
+    flush_every_n: u64,
+    flush_every: Duration,
+    since_flush: u64,
+    last_flush: Instant,
+}
+
+impl MetricsBuffer {
+    pub fn new(flush_every_n: u64, flush_every: Duration) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            flush_every_n,
+            flush_every,
+            since_flush: 0,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Feed one run's result into its bucket, flushing to `sink` if the
+    /// count or time threshold has been crossed.
+    pub fn record(
+        &mut self,
+        key: MetricsKey,
+        result: &RunResult,
+        fence_violation: bool,
+        sink: &mut dyn MetricsSink,
+    ) -> Result<()> {
+        self.buckets.entry(key).or_default().record(result, fence_violation);
+        self.since_flush += 1;
+
+        if self.since_flush >= self.flush_every_n || self.last_flush.elapsed() >= self.flush_every
+        {
+            self.flush(sink)?;
+        }
+        Ok(())
+    }
+
+    /// Emit every bucket's current counters/histogram to `sink` and reset
+    /// the flush timer/counter (bucket contents themselves are cumulative
+    /// across the whole sweep, not reset).
+    pub fn flush(&mut self, sink: &mut dyn MetricsSink) -> Result<()> {
+        for (key, stats) in &self.buckets {
+            let prefix = format!("nvme_lite.{}", key);
+            sink.counter(&format!("{}.total_runs", prefix), stats.total_runs)?;
+            sink.counter(&format!("{}.runs_with_reset", prefix), stats.runs_with_reset)?;
+            sink.counter(&format!("{}.commands_lost", prefix), stats.commands_lost)?;
+            sink.counter(
+                &format!("{}.fence_violations", prefix),
+                stats.fence_violations,
+            )?;
+            sink.counter(
+                &format!("{}.cross_queue_fence_violations", prefix),
+                stats.cross_queue_fence_violations,
+            )?;
+            for (&peak, &count) in &stats.pending_peak_histogram {
+                sink.gauge(&format!("{}.pending_peak.{}", prefix, peak), count as f64)?;
+            }
+            for (queue_idx, &peak) in stats.queue_peak_max.iter().enumerate() {
+                sink.gauge(&format!("{}.queue_peak.{}", prefix, queue_idx), peak as f64)?;
+            }
+        }
+        sink.flush()?;
+        self.since_flush = 0;
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+
+    pub fn bucket(&self, key: &MetricsKey) -> Option<&BucketStats> {
+        self.buckets.get(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        counters: Vec<(String, u64)>,
+        gauges: Vec<(String, f64)>,
+        flushed: bool,
+    }
+
+    impl MetricsSink for RecordingSink {
+        fn gauge(&mut self, name: &str, value: f64) -> Result<()> {
+            self.gauges.push((name.to_string(), value));
+            Ok(())
+        }
+        fn counter(&mut self, name: &str, value: u64) -> Result<()> {
+            self.counters.push((name.to_string(), value));
+            Ok(())
+        }
+        fn timing(&mut self, _name: &str, _millis: u64) -> Result<()> {
+            Ok(())
+        }
+        fn flush(&mut self) -> Result<()> {
+            self.flushed = true;
+            Ok(())
+        }
+    }
+
+    fn result(had_reset: bool, commands_lost: u32, pending_peak: u32) -> RunResult {
+        RunResult {
+            run_id: "test".to_string(),
+            pending_left: 0,
+            pending_peak,
+            had_reset,
+            commands_lost,
+            queue_peaks: Vec::new(),
+            cross_queue_fence_violations: 0,
+        }
+    }
+
+    #[test]
+    fn test_bucket_records_counters() {
+        let mut buffer = MetricsBuffer::new(1_000, Duration::from_secs(3600));
+        let mut sink = RecordingSink::default();
+        let key = MetricsKey {
+            policy: Policy::FIFO,
+            bound_k: BoundK::Infinite,
+            fault_mode: FaultMode::NONE,
+        };
+
+        buffer
+            .record(key, &result(false, 0, 3), false, &mut sink)
+            .unwrap();
+        buffer
+            .record(key, &result(true, 2, 5), true, &mut sink)
+            .unwrap();
+
+        let stats = buffer.bucket(&key).unwrap();
+        assert_eq!(stats.total_runs, 2);
+        assert_eq!(stats.runs_with_reset, 1);
+        assert_eq!(stats.commands_lost, 2);
+        assert_eq!(stats.fence_violations, 1);
+        assert_eq!(*stats.pending_peak_histogram().get(&3).unwrap(), 1);
+        assert_eq!(*stats.pending_peak_histogram().get(&5).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_bucket_tracks_queue_peak_max_across_runs() {
+        let mut buffer = MetricsBuffer::new(1_000, Duration::from_secs(3600));
+        let mut sink = RecordingSink::default();
+        let key = MetricsKey {
+            policy: Policy::FIFO,
+            bound_k: BoundK::Infinite,
+            fault_mode: FaultMode::NONE,
+        };
+
+        let mut r1 = result(false, 0, 3);
+        r1.queue_peaks = vec![2, 5];
+        let mut r2 = result(false, 0, 3);
+        r2.queue_peaks = vec![4, 1];
+        r2.cross_queue_fence_violations = 1;
+
+        buffer.record(key, &r1, false, &mut sink).unwrap();
+        buffer.record(key, &r2, true, &mut sink).unwrap();
+
+        let stats = buffer.bucket(&key).unwrap();
+        assert_eq!(stats.queue_peak_max(), &[4, 5]);
+        assert_eq!(stats.cross_queue_fence_violations, 1);
+    }
+
+    #[test]
+    fn test_flush_every_n_triggers_sink_flush() {
+        let mut buffer = MetricsBuffer::new(2, Duration::from_secs(3600));
+        let mut sink = RecordingSink::default();
+        let key = MetricsKey {
+            policy: Policy::FIFO,
+            bound_k: BoundK::Infinite,
+            fault_mode: FaultMode::NONE,
+        };
+
+        buffer
+            .record(key, &result(false, 0, 1), false, &mut sink)
+            .unwrap();
+        assert!(!sink.flushed);
+
+        buffer
+            .record(key, &result(false, 0, 1), false, &mut sink)
+            .unwrap();
+        assert!(sink.flushed);
+        assert!(sink
+            .counters
+            .iter()
+            .any(|(name, value)| name.ends_with(".total_runs") && *value == 2));
+    }
+
+    #[test]
+    fn test_prometheus_sink_writes_type_and_value_lines() {
+        let tmp = std::env::temp_dir().join("test_metrics_prometheus.txt");
+        let mut sink = PrometheusFileSink::new(&tmp);
+        sink.counter("nvme_lite.total_runs", 3).unwrap();
+        sink.gauge("nvme_lite.pending_peak.5", 1.0).unwrap();
+        sink.flush().unwrap();
+
+        let content = std::fs::read_to_string(&tmp).unwrap();
+        assert!(content.contains("# TYPE nvme_lite_total_runs counter"));
+        assert!(content.contains("nvme_lite_total_runs 3"));
+        assert!(content.contains("# TYPE nvme_lite_pending_peak_5 gauge"));
+    }
+
+    #[test]
+    fn test_sanitize_prometheus_name_replaces_dots_and_leading_digit() {
+        assert_eq!(
+            sanitize_prometheus_name("nvme_lite.BATCHED_2_NONE.total_runs"),
+            "nvme_lite_BATCHED_2_NONE_total_runs"
+        );
+        assert_eq!(sanitize_prometheus_name("5xx_errors"), "_5xx_errors");
+    }
+}
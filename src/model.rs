@@ -13,6 +13,58 @@ use std::collections::HashMap;
 /// Device storage size (in u32 words)
 const STORAGE_SIZE: usize = 1024;
 
+/// Execute `command` against a host/device storage pair and return its
+/// `(status, output)`. Free function (rather than a method) so both
+/// `NvmeLiteModel` and `MultiQueueModel` can share one storage semantics
+/// without either owning the other's queue bookkeeping.
+pub(crate) fn execute_command_on(
+    host_storage: &mut [u32],
+    dev_storage: &mut [u32],
+    command: &Command,
+) -> (Status, u32) {
+    match command {
+        Command::WRITE { lba, len, pattern } => {
+            let start = *lba as usize;
+            let end = start + *len as usize;
+
+            if end > host_storage.len() {
+                return (Status::ERR, 0);
+            }
+
+            host_storage[start..end].fill(*pattern);
+            (Status::OK, 0)
+        }
+        Command::READ { lba, len } => {
+            let start = *lba as usize;
+            let end = start + *len as usize;
+
+            if end > dev_storage.len() {
+                return (Status::ERR, 0);
+            }
+
+            // Compute simple hash of read data
+            let mut hash: u32 = 0;
+            for &word in &dev_storage[start..end] {
+                hash = hash.wrapping_mul(31).wrapping_add(word);
+            }
+            (Status::OK, hash)
+        }
+        Command::FENCE => {
+            // Fence itself just completes OK
+            (Status::OK, 0)
+        }
+        Command::WRITE_VISIBLE { lba, len } => {
+            let start = *lba as usize;
+            let end = start + *len as usize;
+            if end > dev_storage.len() {
+                return (Status::ERR, 0);
+            }
+            dev_storage[start..end].copy_from_slice(&host_storage[start..end]);
+            (Status::OK, 0)
+        }
+    }
+}
+
 /// Terminal status of a command
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
@@ -197,51 +249,7 @@ impl NvmeLiteModel {
 
     /// Execute a command and return (status, output)
     fn execute_command(&mut self, command: &Command) -> (Status, u32) {
-        match command {
-            Command::WRITE { lba, len, pattern } => {
-                let start = *lba as usize;
-                let end = start + *len as usize;
-
-                if end > self.host_storage.len() {
-                    return (Status::ERR, 0);
-                }
-
-                for i in start..end {
-                    self.host_storage[i] = *pattern;
-                }
-                (Status::OK, 0)
-            }
-            Command::READ { lba, len } => {
-                let start = *lba as usize;
-                let end = start + *len as usize;
-
-                if end > self.dev_storage.len() {
-                    return (Status::ERR, 0);
-                }
-
-                // Compute simple hash of read data
-                let mut hash: u32 = 0;
-                for i in start..end {
-                    hash = hash.wrapping_mul(31).wrapping_add(self.dev_storage[i]);
-                }
-                (Status::OK, hash)
-            }
-            Command::FENCE => {
-                // Fence itself just completes OK
-                (Status::OK, 0)
-            }
-            Command::WRITE_VISIBLE { lba, len } => {
-                let start = *lba as usize;
-                let end = start + *len as usize;
-                if end > self.dev_storage.len() {
-                    return (Status::ERR, 0);
-                }
-                for i in start..end {
-                    self.dev_storage[i] = self.host_storage[i];
-                }
-                (Status::OK, 0)
-            }
-        }
+        execute_command_on(&mut self.host_storage, &mut self.dev_storage, command)
     }
 
     /// Perform a reset - clears all pending commands
@@ -271,14 +279,35 @@ impl NvmeLiteModel {
     }
 
     /// Get completion order (list of cmd_ids in completion order)
-    #[allow(dead_code)]
     pub fn get_complete_order(&self) -> Vec<u32> {
         self.completed.iter().map(|r| r.cmd_id).collect()
     }
 
+    /// Check that completion order respects every FENCE submitted so far.
+    /// Returns the first violation found as (fence_cmd_id, cmd_id_that_completed_after_it),
+    /// or `None` if every non-fence command submitted before a fence (lower cmd_id) that has
+    /// completed did so before the fence itself completed (commands still pending, and
+    /// fences whose own completion hasn't happened, are not a violation here).
+    pub fn check_fence_invariant(&self) -> Option<(u32, u32)> {
+        let order = self.get_complete_order();
+        for (fence_cmd_id, before) in self.get_fence_data() {
+            let fence_pos = match order.iter().position(|&c| c == fence_cmd_id) {
+                Some(pos) => pos,
+                None => continue, // fence itself hasn't completed yet
+            };
+            for cmd_id in before {
+                if let Some(pos) = order.iter().position(|&c| c == cmd_id) {
+                    if pos > fence_pos {
+                        return Some((fence_cmd_id, cmd_id));
+                    }
+                }
+            }
+        }
+        None
+    }
+
     /// Get fence data for FE calculation
     /// Returns Vec of (fence_cmd_id, commands_before_fence)
-    #[allow(dead_code)]
     pub fn get_fence_data(&self) -> Vec<(u32, Vec<u32>)> {
         let mut result = Vec::new();
 
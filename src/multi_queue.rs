@@ -0,0 +1,419 @@
+//! Multi-queue NVMe model: multiple SQ/CQ ring-buffer pairs with
+//! deterministic cross-queue interleaving.
+//!
+//! `NvmeLiteModel` models a single implicit submission/completion queue,
+//! but real NVMe exposes multiple SQ/CQ pairs where ordering across queues
+//! is unconstrained — exactly where robustness bugs hide. `MultiQueueModel`
+//! routes each command to one of a fixed number of queues, each a
+//! fixed-size ring buffer that toggles its phase tag on wrap (the real
+//! NVMe mechanism a driver uses to detect new entries), while keeping a
+//! single global, sorted-by-`cmd_id` pending set so `FENCE` still means
+//! "every lower cmd_id, in any queue, completes first."
+
+use crate::model::{execute_command_on, CommandResult, PendingCommand, Status};
+use crate::seed::Command;
+use std::collections::HashMap;
+
+/// Device storage size (in u32 words), matching `NvmeLiteModel`.
+const STORAGE_SIZE: usize = 1024;
+
+/// One SQ/CQ ring-buffer pair.
+#[derive(Debug)]
+struct Queue {
+    capacity: usize,
+    sq_tail: usize,
+    sq_phase: bool,
+    cq_head: usize,
+    cq_phase: bool,
+    peak: u32,
+}
+
+impl Queue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            sq_tail: 0,
+            sq_phase: true,
+            cq_head: 0,
+            cq_phase: true,
+            peak: 0,
+        }
+    }
+
+    /// Advance the SQ tail by one slot, toggling `sq_phase` on wrap.
+    fn advance_sq(&mut self) {
+        self.sq_tail += 1;
+        if self.sq_tail >= self.capacity {
+            self.sq_tail = 0;
+            self.sq_phase = !self.sq_phase;
+        }
+    }
+
+    /// Advance the CQ head by one slot, toggling `cq_phase` on wrap.
+    fn advance_cq(&mut self) {
+        self.cq_head += 1;
+        if self.cq_head >= self.capacity {
+            self.cq_head = 0;
+            self.cq_phase = !self.cq_phase;
+        }
+    }
+}
+
+/// Multi-queue NVMe model state.
+#[derive(Debug)]
+pub struct MultiQueueModel {
+    host_storage: Vec<u32>,
+    dev_storage: Vec<u32>,
+
+    queues: Vec<Queue>,
+
+    /// All submitted commands, shared across queues (for fence/order tracking).
+    submitted: Vec<PendingCommand>,
+    /// cmd_id -> (index in `submitted`, queue index)
+    pending: HashMap<u32, (usize, usize)>,
+    completed: Vec<CommandResult>,
+    /// cmd_id -> queue index it was routed to, recorded at completion time
+    /// (kept past `complete()` so fence-violation analysis can tell which
+    /// queue each completed command came from).
+    completion_queue: HashMap<u32, usize>,
+
+    next_cmd_id: u32,
+    current_fence_id: u32,
+    fence_tracking: HashMap<u32, (u32, u32)>,
+
+    pending_peak: u32,
+    had_reset: bool,
+    commands_lost_to_reset: u32,
+
+    /// Round-robin cursor used when `submit` isn't given an explicit queue.
+    next_queue_rr: usize,
+}
+
+impl MultiQueueModel {
+    /// Create a model with `num_queues` SQ/CQ pairs, each holding up to
+    /// `queue_depth` ring slots.
+    pub fn new(num_queues: usize, queue_depth: usize) -> Self {
+        assert!(num_queues > 0, "MultiQueueModel needs at least one queue");
+        Self {
+            host_storage: vec![0; STORAGE_SIZE],
+            dev_storage: vec![0; STORAGE_SIZE],
+            queues: (0..num_queues).map(|_| Queue::new(queue_depth)).collect(),
+            submitted: Vec::new(),
+            pending: HashMap::new(),
+            completed: Vec::new(),
+            completion_queue: HashMap::new(),
+            next_cmd_id: 0,
+            current_fence_id: 0,
+            fence_tracking: HashMap::new(),
+            pending_peak: 0,
+            had_reset: false,
+            commands_lost_to_reset: 0,
+            next_queue_rr: 0,
+        }
+    }
+
+    /// Number of SQ/CQ pairs in this model.
+    pub fn num_queues(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Submit a command, routing it to `queue` if given or round-robin
+    /// otherwise. Returns `(cmd_id, queue_idx, is_fence, fence_id_if_fence)`.
+    pub fn submit(&mut self, command: Command, queue: Option<usize>) -> (u32, usize, bool, Option<u32>) {
+        let queue_idx = queue.unwrap_or_else(|| {
+            let idx = self.next_queue_rr;
+            self.next_queue_rr = (self.next_queue_rr + 1) % self.queues.len();
+            idx
+        });
+
+        let cmd_id = self.next_cmd_id;
+        self.next_cmd_id += 1;
+
+        let is_fence = matches!(command, Command::FENCE);
+        let fence_id = if is_fence {
+            let fid = self.current_fence_id;
+            self.current_fence_id += 1;
+            let commands_before = cmd_id;
+            self.fence_tracking.insert(fid, (commands_before, 0));
+            Some(fid)
+        } else {
+            None
+        };
+
+        let pending_cmd = PendingCommand {
+            cmd_id,
+            command,
+            fence_id,
+        };
+        let idx = self.submitted.len();
+        self.submitted.push(pending_cmd);
+        self.pending.insert(cmd_id, (idx, queue_idx));
+
+        let q = &mut self.queues[queue_idx];
+        q.advance_sq();
+
+        let current_pending = self.pending.len() as u32;
+        if current_pending > self.pending_peak {
+            self.pending_peak = current_pending;
+        }
+        let queue_pending = self
+            .pending
+            .values()
+            .filter(|&&(_, q)| q == queue_idx)
+            .count() as u32;
+        if queue_pending > q.peak {
+            q.peak = queue_pending;
+        }
+
+        (cmd_id, queue_idx, is_fence, fence_id)
+    }
+
+    /// Get pending command IDs in global canonical order (sorted by
+    /// cmd_id, across all queues), so `FENCE` semantics stay
+    /// queue-agnostic: "all lower cmd_ids must complete first."
+    pub fn get_pending_canonical(&self) -> Vec<u32> {
+        let mut pending: Vec<u32> = self.pending.keys().copied().collect();
+        pending.sort();
+        pending
+    }
+
+    /// Get pending command IDs routed to a specific queue, in submission order.
+    pub fn get_pending_in_queue(&self, queue_idx: usize) -> Vec<u32> {
+        let mut ids: Vec<u32> = self
+            .pending
+            .iter()
+            .filter(|&(_, &(_, q))| q == queue_idx)
+            .map(|(&cmd_id, _)| cmd_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn pending_peak(&self) -> u32 {
+        self.pending_peak
+    }
+
+    /// Peak pending depth reached by each queue, indexed by queue index.
+    pub fn queue_peaks(&self) -> Vec<u32> {
+        self.queues.iter().map(|q| q.peak).collect()
+    }
+
+    /// Current SQ/CQ phase tags for `queue_idx`, as `(sq_phase, cq_phase)`.
+    pub fn phase_tags(&self, queue_idx: usize) -> (bool, bool) {
+        let q = &self.queues[queue_idx];
+        (q.sq_phase, q.cq_phase)
+    }
+
+    /// Complete a command by its cmd_id, posting it to its queue's CQ.
+    /// Returns the result if the command was pending, `None` otherwise.
+    pub fn complete(&mut self, cmd_id: u32, force_status: Option<Status>) -> Option<CommandResult> {
+        let (idx, queue_idx) = self.pending.remove(&cmd_id)?;
+        let command = self.submitted[idx].command.clone();
+
+        let (status, output) = if let Some(forced) = force_status {
+            (forced, 0)
+        } else {
+            execute_command_on(&mut self.host_storage, &mut self.dev_storage, &command)
+        };
+
+        let result = CommandResult {
+            cmd_id,
+            status,
+            output,
+        };
+
+        for (_fid, (total, completed)) in self.fence_tracking.iter_mut() {
+            if cmd_id < *total {
+                *completed += 1;
+            }
+        }
+
+        self.queues[queue_idx].advance_cq();
+        self.completion_queue.insert(cmd_id, queue_idx);
+        self.completed.push(result.clone());
+        Some(result)
+    }
+
+    /// Perform a reset - clears all pending commands across every queue.
+    pub fn reset(&mut self) -> u32 {
+        let pending_before = self.pending.len() as u32;
+        self.commands_lost_to_reset = pending_before;
+        self.pending.clear();
+        self.had_reset = true;
+        pending_before
+    }
+
+    pub fn commands_lost(&self) -> u32 {
+        self.commands_lost_to_reset
+    }
+
+    pub fn had_reset(&self) -> bool {
+        self.had_reset
+    }
+
+    /// Get completion order (list of cmd_ids in completion order), same
+    /// meaning as `NvmeLiteModel::get_complete_order`.
+    pub fn get_complete_order(&self) -> Vec<u32> {
+        self.completed.iter().map(|r| r.cmd_id).collect()
+    }
+
+    /// Get fence data for FE calculation: `(fence_cmd_id, commands_before_fence)`.
+    pub fn get_fence_data(&self) -> Vec<(u32, Vec<u32>)> {
+        let mut result = Vec::new();
+        for pending in &self.submitted {
+            if pending.fence_id.is_some() {
+                let fence_cmd_id = pending.cmd_id;
+                let before: Vec<u32> = self
+                    .submitted
+                    .iter()
+                    .filter(|p| p.cmd_id < fence_cmd_id && p.fence_id.is_none())
+                    .map(|p| p.cmd_id)
+                    .collect();
+                result.push((fence_cmd_id, before));
+            }
+        }
+        result
+    }
+
+    /// Check the same global fence invariant as `NvmeLiteModel`, returning
+    /// the first violation as `(fence_cmd_id, cmd_id_that_completed_after_it)`.
+    pub fn check_fence_invariant(&self) -> Option<(u32, u32)> {
+        let order = self.get_complete_order();
+        for (fence_cmd_id, before) in self.get_fence_data() {
+            let fence_pos = match order.iter().position(|&c| c == fence_cmd_id) {
+                Some(pos) => pos,
+                None => continue,
+            };
+            for cmd_id in before {
+                if let Some(pos) = order.iter().position(|&c| c == cmd_id) {
+                    if pos > fence_pos {
+                        return Some((fence_cmd_id, cmd_id));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Count of fence violations where the late command was routed to a
+    /// different queue than the fence itself — i.e. caused specifically by
+    /// cross-queue reordering rather than same-queue completion slack.
+    pub fn cross_queue_fence_violations(&self) -> u32 {
+        let order = self.get_complete_order();
+        let mut count = 0;
+        for (fence_cmd_id, before) in self.get_fence_data() {
+            let fence_pos = match order.iter().position(|&c| c == fence_cmd_id) {
+                Some(pos) => pos,
+                None => continue,
+            };
+            let fence_queue = self.completion_queue.get(&fence_cmd_id).copied();
+            for cmd_id in before {
+                if let Some(pos) = order.iter().position(|&c| c == cmd_id) {
+                    if pos > fence_pos {
+                        let cmd_queue = self.completion_queue.get(&cmd_id).copied();
+                        if cmd_queue != fence_queue {
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+impl Default for MultiQueueModel {
+    fn default() -> Self {
+        Self::new(2, 16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_robin_routes_across_queues() {
+        let mut model = MultiQueueModel::new(2, 16);
+        let (_, q0, ..) = model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, None);
+        let (_, q1, ..) = model.submit(Command::WRITE { lba: 1, len: 1, pattern: 2 }, None);
+        let (_, q2, ..) = model.submit(Command::WRITE { lba: 2, len: 1, pattern: 3 }, None);
+        assert_eq!(q0, 0);
+        assert_eq!(q1, 1);
+        assert_eq!(q2, 0);
+    }
+
+    #[test]
+    fn test_sq_phase_toggles_on_wrap() {
+        let mut model = MultiQueueModel::new(1, 2);
+        assert!(model.phase_tags(0).0);
+        model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, Some(0));
+        assert!(model.phase_tags(0).0);
+        model.submit(Command::WRITE { lba: 1, len: 1, pattern: 2 }, Some(0));
+        // Tail wrapped after the 2nd submit into a capacity-2 ring.
+        assert!(!model.phase_tags(0).0);
+    }
+
+    #[test]
+    fn test_global_canonical_order_spans_queues() {
+        let mut model = MultiQueueModel::new(2, 16);
+        model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, Some(0));
+        model.submit(Command::WRITE { lba: 1, len: 1, pattern: 2 }, Some(1));
+        assert_eq!(model.get_pending_canonical(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_per_queue_peak_tracked_independently() {
+        let mut model = MultiQueueModel::new(2, 16);
+        model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, Some(0));
+        model.submit(Command::WRITE { lba: 1, len: 1, pattern: 2 }, Some(0));
+        model.submit(Command::WRITE { lba: 2, len: 1, pattern: 3 }, Some(1));
+        assert_eq!(model.queue_peaks(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_cross_queue_fence_violation_detected() {
+        let mut model = MultiQueueModel::new(2, 16);
+        // w0 precedes the fence (lower cmd_id) but is routed to a
+        // different queue than the fence itself.
+        let (w0, ..) = model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, Some(1));
+        let (fence_id, ..) = model.submit(Command::FENCE, Some(0));
+
+        // Completing the fence before w0 violates the barrier.
+        model.complete(fence_id, None);
+        model.complete(w0, None);
+
+        assert!(model.check_fence_invariant().is_some());
+        assert_eq!(model.cross_queue_fence_violations(), 1);
+    }
+
+    #[test]
+    fn test_same_queue_fence_violation_not_counted_as_cross_queue() {
+        let mut model = MultiQueueModel::new(2, 16);
+        let (w0, ..) = model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, Some(0));
+        let (fence_id, ..) = model.submit(Command::FENCE, Some(0));
+
+        // Same-queue reordering also violates the fence, but it isn't
+        // "caused by" cross-queue interleaving.
+        model.complete(fence_id, None);
+        model.complete(w0, None);
+
+        assert!(model.check_fence_invariant().is_some());
+        assert_eq!(model.cross_queue_fence_violations(), 0);
+    }
+
+    #[test]
+    fn test_reset_clears_pending_across_all_queues() {
+        let mut model = MultiQueueModel::new(2, 16);
+        model.submit(Command::WRITE { lba: 0, len: 1, pattern: 1 }, Some(0));
+        model.submit(Command::WRITE { lba: 1, len: 1, pattern: 2 }, Some(1));
+        let lost = model.reset();
+        assert_eq!(lost, 2);
+        assert_eq!(model.pending_count(), 0);
+        assert!(model.had_reset());
+    }
+}
@@ -4,9 +4,10 @@
 
 use crate::logging::{FaultMode, Logger, SerializedSchedule, SubmitWindow};
 use crate::model::{NvmeLiteModel, Status};
-use crate::scheduler::{BoundK, Policy, Scheduler};
+use crate::multi_queue::MultiQueueModel;
+use crate::scheduler::{BoundK, Policy, ReplayScheduler, Scheduler};
 use crate::seed::Seed;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::Path;
 
 /// Configuration for a single run
@@ -41,6 +42,12 @@ pub struct RunResult {
     pub pending_peak: u32,
     pub had_reset: bool,
     pub commands_lost: u32,
+    /// Peak pending depth reached by each queue, indexed by queue index.
+    /// Empty for single-queue runs (`execute_run`/`execute_replay`).
+    pub queue_peaks: Vec<u32>,
+    /// Fence violations caused specifically by cross-queue reordering.
+    /// Always 0 for single-queue runs.
+    pub cross_queue_fence_violations: u32,
 }
 
 /// Execute a single run
@@ -50,6 +57,20 @@ pub fn execute_run(
     out_log: &Path,
     out_schedule: Option<&Path>,
 ) -> Result<RunResult> {
+    let (result, _model) = execute_run_with_model(seed, config, out_log, out_schedule)?;
+    Ok(result)
+}
+
+/// Execute a single run, also returning the final model state.
+///
+/// Used by callers that need to inspect post-run invariants (fence ordering,
+/// completion order) beyond what `RunResult` summarizes, e.g. the fuzz harness.
+pub fn execute_run_with_model(
+    seed: &Seed,
+    config: &RunConfig,
+    out_log: &Path,
+    out_schedule: Option<&Path>,
+) -> Result<(RunResult, NvmeLiteModel)> {
     let mut model = NvmeLiteModel::new();
     let mut scheduler = Scheduler::new(config.policy, config.bound_k, config.schedule_seed);
     let mut logger = Logger::new();
@@ -129,11 +150,10 @@ pub fn execute_run(
         } else if submit_ok && complete_ok {
             // Use RNG bit to decide
             let bit = scheduler.next_bit();
+            schedule.add_bit(bit);
             bit == 1
-        } else if complete_ok {
-            true
         } else {
-            false
+            complete_ok
         };
 
         if do_complete {
@@ -217,15 +237,357 @@ pub fn execute_run(
         }
     }
 
+    let result = RunResult {
+        run_id,
+        pending_left,
+        pending_peak: final_peak,
+        had_reset: model.had_reset(),
+        commands_lost: model.commands_lost(),
+        queue_peaks: Vec::new(),
+        cross_queue_fence_violations: 0,
+    };
+    Ok((result, model))
+}
+
+/// Execute a single run against `MultiQueueModel` instead of the default
+/// single-queue `NvmeLiteModel`, routing each submit to one of `num_queues`
+/// queues deterministically via `scheduler.next_queue`. Mirrors
+/// `execute_run_with_model`'s submit/complete/fault loop; the differences are
+/// per-command queue routing, the extra per-queue/cross-queue fields on the
+/// returned `RunResult`, and that no `SerializedSchedule` is recorded or dumped.
+pub fn execute_run_multi_queue(
+    seed: &Seed,
+    config: &RunConfig,
+    num_queues: usize,
+    queue_depth: usize,
+    out_log: &Path,
+) -> Result<(RunResult, MultiQueueModel)> {
+    let mut model = MultiQueueModel::new(num_queues, queue_depth);
+    let mut scheduler = Scheduler::new(config.policy, config.bound_k, config.schedule_seed);
+    let mut logger = Logger::new();
+
+    let n_cmds = seed.commands.len();
+    let run_id = config.run_id();
+    let submit_window = config.submit_window.value();
+
+    logger.write_header_with_window(
+        &run_id,
+        &seed.seed_id,
+        config.schedule_seed,
+        config.policy,
+        config.bound_k,
+        config.fault_mode,
+        n_cmds,
+        &config.scheduler_version,
+        &config.git_commit,
+        config.submit_window,
+    );
+
+    let mut next_cmd: usize = 0;
+    let mut pending_peak: u32 = 0;
+    let mut step_count = 0;
+    let fault_step = if config.fault_mode != FaultMode::NONE {
+        Some(n_cmds / 2)
+    } else {
+        None
+    };
+    let mut _fault_injected = false;
+    let mut stop_submits = false;
+
+    let mut batch_remaining: usize = 0;
+    const BATCH_SIZE: usize = 4;
+
+    loop {
+        let pending_count = model.pending_count();
+        let submit_ok = pending_count < submit_window && next_cmd < n_cmds && !stop_submits;
+        let complete_ok = pending_count > 0;
+
+        if !submit_ok && !complete_ok {
+            break;
+        }
+
+        let do_complete = if config.policy == Policy::BATCHED && batch_remaining > 0 {
+            true
+        } else if submit_ok && complete_ok {
+            scheduler.next_bit() == 1
+        } else {
+            complete_ok
+        };
+
+        if do_complete {
+            if let Some(fs) = fault_step {
+                if step_count >= fs && !_fault_injected {
+                    match config.fault_mode {
+                        FaultMode::TIMEOUT => {
+                            let pending = model.get_pending_canonical();
+                            if let Some(&cmd_id) = pending.first() {
+                                if let Some(result) = model.complete(cmd_id, Some(Status::TIMEOUT))
+                                {
+                                    logger.log_complete(
+                                        result.cmd_id,
+                                        result.status,
+                                        result.output,
+                                    );
+                                }
+                            }
+                            _fault_injected = true;
+                            stop_submits = true;
+                            step_count += 1;
+                            continue;
+                        }
+                        FaultMode::RESET => {
+                            let pending_before = model.reset();
+                            logger.log_reset("INJECTED", pending_before);
+                            _fault_injected = true;
+                            break;
+                        }
+                        FaultMode::NONE => {}
+                    }
+                }
+            }
+
+            let pending = model.get_pending_canonical();
+
+            if config.policy == Policy::BATCHED && batch_remaining == 0 && !pending.is_empty() {
+                batch_remaining = std::cmp::min(BATCH_SIZE, pending.len());
+            }
+
+            if let Some(decision) = scheduler.pick_next(&pending) {
+                if let Some(result) = model.complete(decision.cmd_id, None) {
+                    logger.log_complete(result.cmd_id, result.status, result.output);
+                    if config.policy == Policy::BATCHED && batch_remaining > 0 {
+                        batch_remaining -= 1;
+                    }
+                }
+            }
+            step_count += 1;
+        } else {
+            let queue_idx = scheduler.next_queue(num_queues);
+            let command = &seed.commands[next_cmd];
+            let (cmd_id, _queue_idx, is_fence, fence_id) =
+                model.submit(command.clone(), Some(queue_idx));
+            logger.log_submit(cmd_id, command.type_name());
+            if is_fence {
+                if let Some(fid) = fence_id {
+                    logger.log_fence(fid);
+                }
+            }
+            next_cmd += 1;
+            let current = model.pending_count() as u32;
+            if current > pending_peak {
+                pending_peak = current;
+            }
+        }
+    }
+
+    let pending_left = model.pending_count() as u32;
+    let final_peak = std::cmp::max(pending_peak, model.pending_peak());
+    logger.log_run_end(pending_left, final_peak);
+    logger.write_to_file(out_log)?;
+
+    let result = RunResult {
+        run_id,
+        pending_left,
+        pending_peak: final_peak,
+        had_reset: model.had_reset(),
+        commands_lost: model.commands_lost(),
+        queue_peaks: model.queue_peaks(),
+        cross_queue_fence_violations: model.cross_queue_fence_violations(),
+    };
+    Ok((result, model))
+}
+
+/// Re-run `seed` strictly from a previously dumped `SerializedSchedule`
+/// instead of re-rolling the scheduler's RNG.
+///
+/// This decouples reproduction from scheduler/runner internals: a schedule
+/// captured on one build can be replayed verbatim even after `scheduler_version`
+/// changes, as long as the recorded decisions still fit the candidate window.
+/// `submit_window` isn't captured in the schedule, so replay assumes
+/// `SubmitWindow::Infinite` (the default every run uses unless overridden).
+pub fn execute_replay(seed: &Seed, schedule_path: &Path, out_log: &Path) -> Result<RunResult> {
+    let schedule = SerializedSchedule::load(schedule_path)
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .with_context(|| format!("failed to load schedule: {}", schedule_path.display()))?;
+
+    let policy: Policy = schedule
+        .policy
+        .parse()
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let bound_k = BoundK::parse(&schedule.bound_k).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let fault_mode: FaultMode = schedule
+        .fault_mode
+        .parse()
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let seed_id = schedule.seed_id.clone();
+    let schedule_seed = schedule.schedule_seed;
+
+    let mut model = NvmeLiteModel::new();
+    let mut replay = ReplayScheduler::from_schedule(schedule);
+    let mut logger = Logger::new();
+
+    let n_cmds = seed.commands.len();
+    let run_id = format!("{}_{}_{}_{}_replay", seed_id, policy, bound_k, schedule_seed);
+    let submit_window = SubmitWindow::Infinite.value();
+
+    logger.write_header_with_window(
+        &run_id,
+        &seed_id,
+        schedule_seed,
+        policy,
+        bound_k,
+        fault_mode,
+        n_cmds,
+        "replay",
+        "",
+        SubmitWindow::Infinite,
+    );
+
+    let do_submit = |model: &mut NvmeLiteModel, logger: &mut Logger, idx: usize| {
+        let command = &seed.commands[idx];
+        let (cmd_id, is_fence, fence_id) = model.submit(command.clone());
+        logger.log_submit(cmd_id, command.type_name());
+        if is_fence {
+            if let Some(fid) = fence_id {
+                logger.log_fence(fid);
+            }
+        }
+    };
+
+    let mut next_cmd: usize = 0;
+    let mut pending_peak: u32 = 0;
+    let mut step_count = 0;
+    let mut stop_submits = false;
+
+    let mut batch_remaining: usize = 0;
+    const BATCH_SIZE: usize = 4;
+
+    loop {
+        let pending_count = model.pending_count();
+        let submit_ok = pending_count < submit_window && next_cmd < n_cmds && !stop_submits;
+        let complete_ok = pending_count > 0;
+
+        if !submit_ok && !complete_ok {
+            break;
+        }
+
+        let do_complete = if policy == Policy::BATCHED && batch_remaining > 0 {
+            true
+        } else if submit_ok && complete_ok {
+            replay.next_bit()? == 1
+        } else {
+            complete_ok
+        };
+
+        if do_complete {
+            if let Some(fault_type) = replay.take_fault_if_due(step_count) {
+                match fault_type.as_str() {
+                    "TIMEOUT" => {
+                        let pending = model.get_pending_canonical();
+                        if let Some(&cmd_id) = pending.first() {
+                            if let Some(result) = model.complete(cmd_id, Some(Status::TIMEOUT)) {
+                                logger.log_complete(result.cmd_id, result.status, result.output);
+                            }
+                        }
+                        stop_submits = true;
+                        step_count += 1;
+                        continue;
+                    }
+                    "RESET" => {
+                        let pending_before = model.reset();
+                        logger.log_reset("INJECTED", pending_before);
+                        break;
+                    }
+                    other => {
+                        anyhow::bail!("unrecognized recorded fault type: {}", other);
+                    }
+                }
+            }
+
+            let pending = model.get_pending_canonical();
+
+            if policy == Policy::BATCHED && batch_remaining == 0 && !pending.is_empty() {
+                batch_remaining = std::cmp::min(BATCH_SIZE, pending.len());
+            }
+
+            // Same candidate window `Scheduler::get_candidates` uses, so a
+            // recorded pick_index outside it fails instead of picking past bound_k.
+            let window = match bound_k {
+                BoundK::Finite(k) => std::cmp::min(k as usize + 1, pending.len()),
+                BoundK::Infinite => pending.len(),
+            };
+            let decision = replay.pick_next(&pending[..window])?;
+            if let Some(result) = model.complete(decision.cmd_id, None) {
+                logger.log_complete(result.cmd_id, result.status, result.output);
+                if policy == Policy::BATCHED && batch_remaining > 0 {
+                    batch_remaining -= 1;
+                }
+            }
+            step_count += 1;
+        } else {
+            do_submit(&mut model, &mut logger, next_cmd);
+            next_cmd += 1;
+            let current = model.pending_count() as u32;
+            if current > pending_peak {
+                pending_peak = current;
+            }
+        }
+    }
+
+    let pending_left = model.pending_count() as u32;
+    let final_peak = std::cmp::max(pending_peak, model.pending_peak());
+    logger.log_run_end(pending_left, final_peak);
+    logger.write_to_file(out_log)?;
+
     Ok(RunResult {
         run_id,
         pending_left,
         pending_peak: final_peak,
         had_reset: model.had_reset(),
         commands_lost: model.commands_lost(),
+        queue_peaks: Vec::new(),
+        cross_queue_fence_violations: 0,
     })
 }
 
+/// Compare two log files line by line, returning the first diverging line
+/// as `(line_number, expected, actual)`, or `None` if they match (one being a
+/// prefix of the other also counts as a divergence, reported at the first
+/// line past the shorter file).
+///
+/// `skip_header` ignores line 1 (`RUN_HEADER(...)`) of both files before
+/// comparing. A replay log's header legitimately differs from its reference
+/// (`run_id` carries a `_replay` suffix, `scheduler_version`/`git_commit`
+/// describe the replaying build, not the original one) even when the body —
+/// the actual SUBMIT/COMPLETE/FENCE sequence this function exists to verify —
+/// is byte-identical; pass `true` when comparing a replay against its
+/// original run.
+pub fn diff_logs(
+    reference: &Path,
+    observed: &Path,
+    skip_header: bool,
+) -> Result<Option<(usize, String, String)>> {
+    let reference_lines: Vec<String> = std::fs::read_to_string(reference)?
+        .lines()
+        .map(String::from)
+        .collect();
+    let observed_lines: Vec<String> = std::fs::read_to_string(observed)?
+        .lines()
+        .map(String::from)
+        .collect();
+
+    let start = if skip_header { 1 } else { 0 };
+    let max_len = std::cmp::max(reference_lines.len(), observed_lines.len());
+    for i in start..max_len {
+        let expected = reference_lines.get(i).cloned().unwrap_or_default();
+        let actual = observed_lines.get(i).cloned().unwrap_or_default();
+        if expected != actual {
+            return Ok(Some((i + 1, expected, actual)));
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,4 +685,140 @@ mod tests {
         // With window=2, peak should be <= 2
         assert!(result.pending_peak <= 2);
     }
+
+    #[test]
+    fn test_execute_run_multi_queue_routes_and_reports_peaks() {
+        let seed = test_seed();
+        let config = RunConfig {
+            seed_id: "test".to_string(),
+            schedule_seed: 0,
+            policy: Policy::FIFO,
+            bound_k: BoundK::Infinite,
+            fault_mode: FaultMode::NONE,
+            submit_window: SubmitWindow::Infinite,
+            scheduler_version: "test".to_string(),
+            git_commit: "none".to_string(),
+            dump_schedule: false,
+        };
+
+        let tmp = std::env::temp_dir().join("test_multi_queue.log");
+        let (result, model) = execute_run_multi_queue(&seed, &config, 2, 16, &tmp).unwrap();
+
+        assert_eq!(result.pending_left, 0);
+        assert_eq!(result.queue_peaks.len(), 2);
+        assert_eq!(model.num_queues(), 2);
+    }
+
+    #[test]
+    fn test_execute_replay_reproduces_run() {
+        let seed = test_seed();
+        let config = RunConfig {
+            seed_id: "test".to_string(),
+            schedule_seed: 7,
+            policy: Policy::RANDOM,
+            bound_k: BoundK::Infinite,
+            fault_mode: FaultMode::NONE,
+            submit_window: SubmitWindow::Infinite,
+            scheduler_version: "test".to_string(),
+            git_commit: "none".to_string(),
+            dump_schedule: true,
+        };
+
+        let log_path = std::env::temp_dir().join("test_replay_original.log");
+        let schedule_path = std::env::temp_dir().join("test_replay.json");
+        let original = execute_run(&seed, &config, &log_path, Some(&schedule_path)).unwrap();
+
+        let replay_log_path = std::env::temp_dir().join("test_replay_replayed.log");
+        let replayed = execute_replay(&seed, &schedule_path, &replay_log_path).unwrap();
+
+        assert_eq!(replayed.pending_left, original.pending_left);
+        assert_eq!(replayed.pending_peak, original.pending_peak);
+        assert_eq!(replayed.had_reset, original.had_reset);
+        assert_eq!(replayed.commands_lost, original.commands_lost);
+    }
+
+    #[test]
+    fn test_execute_replay_rejects_stale_pick_index() {
+        let mut schedule = crate::logging::SerializedSchedule::new(
+            "test",
+            0,
+            Policy::FIFO,
+            BoundK::Infinite,
+            FaultMode::NONE,
+        );
+        let schedule_path = std::env::temp_dir().join("test_replay_bad.json");
+        // pick_index 99 will never fit a 1-candidate window
+        schedule.add_complete(99);
+        schedule.write_to_file(&schedule_path).unwrap();
+
+        let seed = Seed {
+            seed_id: "test".to_string(),
+            commands: vec![Command::WRITE {
+                lba: 0,
+                len: 1,
+                pattern: 1,
+            }],
+        };
+        let out_log = std::env::temp_dir().join("test_replay_bad.log");
+        let result = execute_replay(&seed, &schedule_path, &out_log);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_diff_logs_detects_first_divergence() {
+        let a = std::env::temp_dir().join("test_diff_a.log");
+        let b = std::env::temp_dir().join("test_diff_b.log");
+        std::fs::write(&a, "line1\nline2\nline3\n").unwrap();
+        std::fs::write(&b, "line1\nDIFFERENT\nline3\n").unwrap();
+
+        let diff = diff_logs(&a, &b, false).unwrap();
+        assert_eq!(diff, Some((2, "line2".to_string(), "DIFFERENT".to_string())));
+    }
+
+    #[test]
+    fn test_diff_logs_identical_returns_none() {
+        let a = std::env::temp_dir().join("test_diff_same.log");
+        std::fs::write(&a, "line1\nline2\n").unwrap();
+        assert_eq!(diff_logs(&a, &a, false).unwrap(), None);
+    }
+
+    #[test]
+    fn test_diff_logs_skip_header_ignores_first_line() {
+        let a = std::env::temp_dir().join("test_diff_header_a.log");
+        let b = std::env::temp_dir().join("test_diff_header_b.log");
+        std::fs::write(&a, "RUN_HEADER(run_id=orig)\nSUBMIT(cmd_id=0, cmd_type=WRITE)\n").unwrap();
+        std::fs::write(
+            &b,
+            "RUN_HEADER(run_id=orig_replay)\nSUBMIT(cmd_id=0, cmd_type=WRITE)\n",
+        )
+        .unwrap();
+
+        assert_eq!(diff_logs(&a, &b, true).unwrap(), None);
+        assert!(diff_logs(&a, &b, false).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_execute_replay_matches_reference_body_skipping_header() {
+        let seed = test_seed();
+        let config = RunConfig {
+            seed_id: "test".to_string(),
+            schedule_seed: 7,
+            policy: Policy::RANDOM,
+            bound_k: BoundK::Infinite,
+            fault_mode: FaultMode::NONE,
+            submit_window: SubmitWindow::Infinite,
+            scheduler_version: "test".to_string(),
+            git_commit: "none".to_string(),
+            dump_schedule: true,
+        };
+
+        let log_path = std::env::temp_dir().join("test_replay_diff_original.log");
+        let schedule_path = std::env::temp_dir().join("test_replay_diff.json");
+        execute_run(&seed, &config, &log_path, Some(&schedule_path)).unwrap();
+
+        let replay_log_path = std::env::temp_dir().join("test_replay_diff_replayed.log");
+        execute_replay(&seed, &schedule_path, &replay_log_path).unwrap();
+
+        assert_eq!(diff_logs(&log_path, &replay_log_path, true).unwrap(), None);
+    }
 }
@@ -4,6 +4,7 @@
 //! - bound_k limits reordering: only the first k+1 pending commands are candidates
 //! - Policies select among candidates: FIFO, RANDOM, ADVERSARIAL, BATCHED
 
+use crate::logging::{ReplayError, ScheduleStep, SerializedSchedule};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
@@ -36,7 +37,7 @@ impl SplitMix64 {
 }
 
 /// Scheduling policy
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Policy {
     /// Complete oldest pending first (smallest cmd_id)
@@ -75,7 +76,7 @@ impl std::str::FromStr for Policy {
 }
 
 /// Bound k value - can be finite or infinite
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum BoundK {
     Finite(u32),
@@ -152,6 +153,16 @@ impl Scheduler {
         self.rng.next_bit()
     }
 
+    /// Pick which of `num_queues` SQ/CQ pairs the next submitted command is
+    /// routed to, for models (e.g. `MultiQueueModel`) with more than one
+    /// queue. It does not choose which queue's completions are serviced:
+    /// completions are still picked from the global pending list by
+    /// `pick_next`. Draws from the same RNG stream as `next_bit`/`pick_next`,
+    /// so a run stays reproducible from its `schedule_seed`.
+    pub fn next_queue(&mut self, num_queues: usize) -> usize {
+        self.rng.gen_index(num_queues)
+    }
+
     /// Get candidates from pending list based on bound_k
     /// pending must be in canonical order (sorted by cmd_id)
     pub fn get_candidates<'a>(&self, pending: &'a [u32]) -> &'a [u32] {
@@ -223,9 +234,79 @@ impl Scheduler {
     }
 }
 
+/// Drives completion/fault decisions strictly from a previously recorded
+/// `SerializedSchedule`, ignoring `SplitMix64` entirely. Mirrors `Scheduler`'s
+/// `next_bit`/`pick_next` API so `execute_replay` can reuse `execute_run`'s
+/// loop structure, just swapping which one supplies the decisions.
+pub struct ReplayScheduler {
+    steps: Vec<ScheduleStep>,
+    next: usize,
+}
+
+impl ReplayScheduler {
+    /// Build a replay scheduler from a loaded schedule.
+    pub fn from_schedule(schedule: SerializedSchedule) -> Self {
+        Self {
+            steps: schedule.steps,
+            next: 0,
+        }
+    }
+
+    /// Replay the next recorded submit/complete bit.
+    pub fn next_bit(&mut self) -> Result<u64, ReplayError> {
+        match self.steps.get(self.next) {
+            Some(ScheduleStep::Bit { bit }) => {
+                let bit = *bit as u64;
+                self.next += 1;
+                Ok(bit)
+            }
+            Some(_) => Err(ReplayError::Truncated(
+                "expected a recorded Bit step".to_string(),
+            )),
+            None => Err(ReplayError::Exhausted),
+        }
+    }
+
+    /// Replay the next recorded COMPLETE event: picks `candidates[pick_index]`.
+    pub fn pick_next(&mut self, candidates: &[u32]) -> Result<Decision, ReplayError> {
+        match self.steps.get(self.next) {
+            Some(ScheduleStep::CompletePick { pick_index }) => {
+                let pick_index = *pick_index;
+                let cmd_id = candidates.get(pick_index).copied().ok_or(
+                    ReplayError::PickIndexOutOfRange {
+                        pick_index,
+                        candidates: candidates.len(),
+                    },
+                )?;
+                self.next += 1;
+                Ok(Decision { pick_index, cmd_id })
+            }
+            Some(_) => Err(ReplayError::Truncated(
+                "expected a recorded CompletePick step".to_string(),
+            )),
+            None => Err(ReplayError::Exhausted),
+        }
+    }
+
+    /// If the next recorded step is a `FAULT` due at `step`, consume and
+    /// return its fault type (`"TIMEOUT"` or `"RESET"`); otherwise leave the
+    /// schedule untouched and return `None`.
+    pub fn take_fault_if_due(&mut self, step: usize) -> Option<String> {
+        match self.steps.get(self.next) {
+            Some(ScheduleStep::FAULT { fault_type, at_step }) if *at_step == step => {
+                let fault_type = fault_type.clone();
+                self.next += 1;
+                Some(fault_type)
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::logging::FaultMode;
 
     #[test]
     fn test_bound_k_candidates() {
@@ -284,4 +365,55 @@ mod tests {
         assert_eq!(decision.cmd_id, 5); // candidates are [0, 5], adversarial picks 5
         assert_eq!(decision.pick_index, 1);
     }
+
+    #[test]
+    fn test_replay_scheduler_replays_recorded_decisions() {
+        let mut schedule =
+            SerializedSchedule::new("test", 0, Policy::FIFO, BoundK::Infinite, FaultMode::NONE);
+        schedule.add_bit(1);
+        schedule.add_complete(1);
+
+        let mut replay = ReplayScheduler::from_schedule(schedule);
+        assert_eq!(replay.next_bit().unwrap(), 1);
+
+        let decision = replay.pick_next(&[2, 5, 7]).unwrap();
+        assert_eq!(decision.pick_index, 1);
+        assert_eq!(decision.cmd_id, 5);
+    }
+
+    #[test]
+    fn test_replay_scheduler_rejects_stale_pick_index() {
+        let mut schedule =
+            SerializedSchedule::new("test", 0, Policy::FIFO, BoundK::Infinite, FaultMode::NONE);
+        schedule.add_complete(5);
+
+        let mut replay = ReplayScheduler::from_schedule(schedule);
+        match replay.pick_next(&[2, 5]) {
+            Err(ReplayError::PickIndexOutOfRange { pick_index, candidates }) => {
+                assert_eq!(pick_index, 5);
+                assert_eq!(candidates, 2);
+            }
+            other => panic!("expected PickIndexOutOfRange, got {:?}", other.map(|d| d.cmd_id)),
+        }
+    }
+
+    #[test]
+    fn test_replay_scheduler_exhausted() {
+        let schedule =
+            SerializedSchedule::new("test", 0, Policy::FIFO, BoundK::Infinite, FaultMode::NONE);
+        let mut replay = ReplayScheduler::from_schedule(schedule);
+        assert!(matches!(replay.next_bit(), Err(ReplayError::Exhausted)));
+    }
+
+    #[test]
+    fn test_replay_scheduler_fault_due_at_step() {
+        let mut schedule =
+            SerializedSchedule::new("test", 0, Policy::FIFO, BoundK::Infinite, FaultMode::RESET);
+        schedule.add_fault("RESET", 3);
+
+        let mut replay = ReplayScheduler::from_schedule(schedule);
+        assert_eq!(replay.take_fault_if_due(2), None);
+        assert_eq!(replay.take_fault_if_due(3), Some("RESET".to_string()));
+        assert_eq!(replay.take_fault_if_due(3), None); // already consumed
+    }
 }